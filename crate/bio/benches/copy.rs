@@ -0,0 +1,43 @@
+use {
+    bio::*,
+    criterion::{
+        criterion_group,
+        criterion_main,
+        Criterion,
+        Throughput,
+    },
+    std::hint::black_box,
+};
+
+const MIB: usize = 1 << 20;
+
+fn copy(c: &mut Criterion) {
+    let data = vec![0xa5u8; MIB];
+    let mut group = c.benchmark_group("copy 1 MiB");
+    group.throughput(Throughput::Bytes(MIB as u64));
+
+    group.bench_function("copy_from_slice", |b| {
+        let mut dest = vec![0u8; MIB];
+        b.iter(|| dest.copy_from_slice(black_box(&data)))
+    });
+    group.bench_function("Buffer::copy_from", |b| {
+        let mut buf = Buffer::from_copy(vec![0u8; MIB]);
+        b.iter(|| {
+            buf.clear();
+            buf.copy_from(black_box(&data))
+        })
+    });
+    group.bench_function("Buffer::transfuse 64 KiB", |b| {
+        let mut buf = Buffer::from_copy(vec![0u8; 64 << 10]);
+        let mut sink = Buffer::from_copy(vec![0u8; MIB]);
+        b.iter(|| {
+            sink.clear();
+            let source = Buffer::from_copy(black_box(data.as_slice())).as_source();
+            buf.transfuse(source, &mut sink)
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, copy);
+criterion_main!(benches);
@@ -9,6 +9,8 @@ use {
 mod buffer;
 mod compact_strategy;
 mod copy_strategy;
+mod dyn_buffer;
+mod std_io;
 #[cfg(test)]
 mod test;
 
@@ -24,6 +26,7 @@ macro_rules! ddoc {
         /// ```rust
         #[doc = include_str!(concat!("../examples/example/", concat!($id, ".rs")))]
         /// ```
+        #[allow(clippy::test_attr_in_doctest)]
         $it
     };
 }
@@ -37,6 +40,49 @@ ddoc!(
     }
 );
 
+/// Combinators available on every [`Source`].
+pub trait SourceExt<T>: Source<T> + Sized {
+    /// Discards the first `n` items.
+    fn skip(self, n: usize) -> flow::Skip<Self, T>
+    where
+        T: Copy + Default,
+    {
+        flow::Skip::new(self, n)
+    }
+
+    /// Ends the source after its first `n` items.
+    fn take(self, n: usize) -> flow::Take<Self> {
+        flow::Take::new(self, n)
+    }
+
+    /// Continues with `other` once this source is depleted.
+    fn chain<B: Source<T>>(self, other: B) -> flow::Chain<Self, B> {
+        flow::Chain::new(self, other)
+    }
+
+    /// Pairs each item with its index, counting from 0.
+    fn enumerate(self) -> flow::Enumerate<Self, T> {
+        flow::Enumerate::new(self)
+    }
+
+    /// Buffers up to `N` upcoming items, to be inspected before being read.
+    fn lookahead<const N: usize>(self) -> flow::Lookahead<N, Self, T>
+    where
+        T: Copy + Default,
+    {
+        flow::Lookahead::new(self)
+    }
+
+    /// Repeats the source indefinitely, restarting it from a clone taken now.
+    fn cycle(self) -> stream::Cycle<Self>
+    where
+        Self: Clone,
+    {
+        stream::Cycle::new(self)
+    }
+}
+impl<S: Source<T>, T> SourceExt<T> for S {}
+
 pub trait Flow<T, U> {
     type Source<S: Source<T>>: Source<U>;
     fn flow<S: Source<T>>(&self, inp: S) -> Self::Source<S>;
@@ -44,6 +90,13 @@ pub trait Flow<T, U> {
 
 pub trait Sink<T> {
     fn sink(&mut self, from: &[T]) -> IO;
+
+    /// Pushes any data held by the sink to its final destination.
+    ///
+    /// Not called by [`Buffer::transfuse()`]; by default, does nothing.
+    fn flush(&mut self) -> IO<()> {
+        Ok(())
+    }
 }
 
 pub trait CopyStrategy<T> {
@@ -68,40 +121,150 @@ pub struct Buffer<D, T, C, P> {
     _item_evidence: PhantomData<T>,
     _copy_strategy: PhantomData<C>,
     _compact_strategy: PhantomData<P>,
+    #[cfg(feature = "metrics")]
+    compaction_stats: CompactionStats,
+}
+
+/// Compactions that shifted items, and items shifted.
+///
+/// Diagnostics only: all stats compare equal, so that they do not tell apart buffers
+/// holding the same items.
+#[cfg(feature = "metrics")]
+#[derive(Default, Copy, Clone, Debug)]
+struct CompactionStats(usize, usize);
+#[cfg(feature = "metrics")]
+impl PartialEq for CompactionStats {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+#[cfg(feature = "metrics")]
+impl Eq for CompactionStats {}
+#[cfg(feature = "metrics")]
+impl PartialOrd for CompactionStats {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+#[cfg(feature = "metrics")]
+impl Ord for CompactionStats {
+    fn cmp(&self, _: &Self) -> std::cmp::Ordering {
+        std::cmp::Ordering::Equal
+    }
 }
 
-fn transfuse_rec<C, P, D, T>(
+/// Everything [`Buffer::transfuse_complete()`] hands back, to inspect or resume from.
+#[derive(Debug)]
+pub struct TransfuseResult<T, S, K> {
+    /// Items written to the sink.
+    pub total: usize,
+    pub source: S,
+    pub sink: K,
+    /// Items read but not written, as the sink stopped accepting them.
+    pub leftover: Vec<T>,
+}
+
+/// A [`Buffer`] whose copy and compaction strategies are chosen at runtime, through
+/// function pointers, instead of through type parameters.
+#[derive(Copy, Clone, Debug)]
+pub struct DynBuffer<D, T> {
+    data: D,
+    span: (usize, usize),
+    copier: fn(&mut [T], &[T]),
+    compactor: fn(&mut [T], Range<usize>),
+}
+
+/// The buffer operations a transfuse cycle is made of, so that [`Buffer`] and
+/// [`DynBuffer`] share one implementation of it.
+trait Transfusable<T> {
+    fn compact(&mut self);
+    fn is_empty(&self) -> bool;
+    fn is_full(&self) -> bool;
+    fn read(&mut self, source: impl Source<T>) -> IO;
+    fn write(&mut self, sink: impl Sink<T>) -> IO;
+}
+
+/// A transfusion in progress: whether the source has been found depleted, and the totals
+/// read and written so far.
+///
+/// [`Buffer::transfuse()`] and its variants are built out of its steps.
+#[derive(Default)]
+struct Transfusion {
+    source_done: bool,
+    read: usize,
+    written: usize,
+}
+
+impl Transfusion {
+    /// Reads from `source` into the buffer's free area.
+    fn read<T>(&mut self, buffer: &mut impl Transfusable<T>, source: impl Source<T>) -> IO {
+        // Optimize/stabilize: not hitting source after it has returned Ok(0)
+        let read = if self.source_done {
+            // We avoid reading source after Ok(0) has been returned, for performance
+            // but also to have a deterministic contract for transfuse():
+            // We have to assume Ok(0) is final.
+            0
+        } else if buffer.is_full() {
+            // The sink is "full" (back-pressure), and so is the buffer: there is no
+            // room to read into, and the Ok(0) a zero-length read would return says
+            // nothing about the source being depleted, so it must not be latched.
+            0
+        } else {
+            let read = buffer.read(source)?;
+            self.source_done = read == 0;
+            read
+        };
+        // Saturate rather than overflow on never-ending streams (e.g. on 32-bit targets).
+        self.read = self.read.saturating_add(read);
+        Ok(read)
+    }
+
+    /// Writes the buffer's available items to `sink`.
+    fn write<T>(&mut self, buffer: &mut impl Transfusable<T>, sink: impl Sink<T>) -> IO {
+        // Do not bother the sink with empty writes, e.g. when the source is empty
+        // from the start.
+        let write = if buffer.is_empty() {
+            0
+        } else {
+            buffer.write(sink)?
+        };
+        self.wrote(write);
+        Ok(write)
+    }
+
+    /// Accounts for `n` items written to the sink other than by [`Self::write()`].
+    fn wrote(&mut self, n: usize) {
+        self.written = self.written.saturating_add(n);
+    }
+
+    /// Runs a compact/read/write cycle, returning whether it made any progress.
+    fn cycle<T>(
+        &mut self,
+        buffer: &mut impl Transfusable<T>,
+        source: impl Source<T>,
+        sink: impl Sink<T>,
+    ) -> IO<bool> {
+        buffer.compact();
+        let read = self.read(buffer, source)?;
+        let write = self.write(buffer, sink)?;
+        Ok(read > 0 || write > 0)
+    }
+}
+
+/// Runs [`Buffer::transfuse()`] as if `total` items had already been written, and the
+/// source had already been found depleted if `source_done`.
+fn transfuse_from<T>(
     source_done: bool,
     total: usize,
-    buffer: &mut Buffer<D, T, C, P>,
+    buffer: &mut impl Transfusable<T>,
     mut source: impl Source<T>,
     mut sink: impl Sink<T>,
-) -> IO
-where
-    C: CopyStrategy<T>,
-    P: CompactStrategy<T>,
-    D: AsMut<[T]> + AsRef<[T]>,
-{
-    buffer.compact();
-
-    // Optimize/stabilize: not hitting source after it has returned Ok(0)
-    let read = if source_done {
-        // We avoid reading source after Ok(0) has been returned, for performance
-        // but also to have a deterministic contract for transfuse():
-        // We have to assume Ok(0) is final.
-        0
-    } else {
-        // Source will return Ok(0) either because the underlying source
-        // is depleted, or the destination sink is "full" (back-pressure),
-        // and the buffer has also become full. Thus, every source-read has a
-        // zero-length destination slice to be read in, and Ok(0) is returned.
-        buffer.read(&mut source)?
+) -> IO {
+    let mut transfusion = Transfusion {
+        source_done,
+        read: 0,
+        written: total,
     };
-    let write = buffer.write(&mut sink)?;
-
-    if read == 0 && write == 0 {
-        Ok(total)
-    } else {
-        transfuse_rec(read == 0, total + write, buffer, source, sink)
-    }
+    while transfusion.cycle(buffer, &mut source, &mut sink)? {}
+    Ok(transfusion.written)
 }
@@ -1,4 +1,10 @@
-use super::*;
+use {
+    super::*,
+    std::time::{
+        Duration,
+        Instant,
+    },
+};
 
 impl<D, T, C, P> Buffer<D, T, C, P> {
     fn new(data: D) -> Self {
@@ -8,6 +14,8 @@ impl<D, T, C, P> Buffer<D, T, C, P> {
             _item_evidence: PhantomData,
             _copy_strategy: PhantomData,
             _compact_strategy: PhantomData,
+            #[cfg(feature = "metrics")]
+            compaction_stats: CompactionStats::default(),
         }
     }
 
@@ -25,6 +33,38 @@ impl<D, T, C, P> Buffer<D, T, C, P> {
     pub fn clear(&mut self) {
         self.span = (0, 0);
     }
+
+    /// How many compactions had to shift items to the front of the buffer, and how many
+    /// items they shifted in total, over the buffer's lifetime.
+    ///
+    /// Frequent compactions during [`Self::transfuse()`] hint at a buffer too small for the
+    /// sink's appetite.
+    #[cfg(feature = "metrics")]
+    pub fn compaction_stats(&self) -> (usize, usize) {
+        let CompactionStats(compactions, shifted) = self.compaction_stats;
+        (compactions, shifted)
+    }
+
+    /// Discards up to `n` leading available items, returning how many were dropped.
+    pub fn shift_out(&mut self, n: usize) -> usize {
+        let n = std::cmp::min(n, self.available());
+        self.span.0 += n;
+        n
+    }
+
+    /// The `(position, limit)` offsets, as accepted by [`Self::from_copy_with_span()`].
+    pub fn span(&self) -> (usize, usize) {
+        self.span
+    }
+
+    /// Swaps in `data` as the backing store, returning the old one.
+    ///
+    /// Unlike [`std::mem::swap()`]ing whole buffers, the strategies stay put and the span
+    /// is reset to empty, as if freshly created over `data`.
+    pub fn replace_data(&mut self, data: D) -> D {
+        self.span = (0, 0);
+        std::mem::replace(&mut self.data, data)
+    }
 }
 
 impl<D, T> Buffer<D, T, SNone, SNone> {
@@ -43,6 +83,45 @@ impl<D, T: Copy> Buffer<D, T, SCopy, SCopy> {
     pub fn from_copy(data: D) -> Self {
         Self::new(data)
     }
+
+    /// Restores a buffer over `data` with the given `(position, limit)` span, as saved from
+    /// [`Self::span()`].
+    ///
+    /// Fails with [`io::ErrorKind::InvalidInput`] unless `start <= end <= data.len()`.
+    pub fn from_copy_with_span(data: D, start: usize, end: usize) -> IO<Self>
+    where
+        D: AsRef<[T]>,
+    {
+        if start > end || end > data.as_ref().len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid buffer span {start}..{end}"),
+            ));
+        }
+        let mut buffer = Self::new(data);
+        buffer.span = (start, end);
+        Ok(buffer)
+    }
+}
+
+impl Buffer<Vec<u8>, u8, SCopy, SCopy> {
+    /// Reads `reader` to its end into a source buffer over all of its bytes.
+    pub fn from_reader<R: io::Read>(mut reader: R) -> IO<Self> {
+        let mut buffer = Self::from_copy(Vec::new());
+        loop {
+            if buffer.is_full() {
+                buffer.free_mut(buffer.len().max(4096));
+            }
+            match buffer.read(stream::Read(&mut reader)) {
+                Ok(0) => break,
+                Ok(_) => (),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+                Err(e) => return Err(e),
+            }
+        }
+        buffer.data.truncate(buffer.span.1);
+        Ok(buffer)
+    }
 }
 
 impl<C, P, D, T> Buffer<D, T, C, P>
@@ -64,6 +143,55 @@ where
     pub fn write(&mut self, mut into: impl Sink<T>) -> IO {
         into.sink(self.as_read()).tap_ok(|n| self.span.0 += n)
     }
+    /// Writes the leading available items for which `pred` holds to `sink`, leaving the
+    /// first item failing it, and the rest, in the buffer.
+    ///
+    /// Returns the number of items the sink accepted.
+    pub fn drain_while<F: FnMut(&T) -> bool>(&mut self, mut pred: F, mut sink: impl Sink<T>) -> IO {
+        let run = self.as_read().iter().take_while(|item| pred(item)).count();
+        if run == 0 {
+            return Ok(0);
+        }
+        sink.sink(&self.as_read()[..run])
+            .tap_ok(|n| self.span.0 += n)
+    }
+    /// Writes once to each of `sinks` in turn (scatter), until the available items run out.
+    ///
+    /// Returns the total written.
+    pub fn write_vectored<S: Sink<T>>(&mut self, sinks: &mut [S]) -> IO {
+        let mut total = 0;
+        for sink in sinks {
+            if self.is_empty() {
+                break;
+            }
+            total += self.write(sink)?;
+        }
+        Ok(total)
+    }
+    /// Writes to the first of `sinks` that accepts any items, falling through to the next
+    /// sink on `Ok(0)`.
+    ///
+    /// Returns the index of the accepting sink and the number of items it accepted, or
+    /// `None` if no sink accepted anything (or the buffer is empty).
+    pub fn write_to_first<S: Sink<T>>(&mut self, sinks: &mut [S]) -> IO<Option<(usize, usize)>> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+        for (i, sink) in sinks.iter_mut().enumerate() {
+            match self.write(sink)? {
+                0 => continue,
+                n => return Ok(Some((i, n))),
+            }
+        }
+        Ok(None)
+    }
+    /// Iterates the available items in groups of `N`, ignoring a trailing remainder of
+    /// less than `N` items.
+    ///
+    /// Panics if `N` is 0.
+    pub fn iter_chunks<const N: usize>(&self) -> impl Iterator<Item = &[T; N]> {
+        self.as_read().as_chunks::<N>().0.iter()
+    }
 
     pub fn len(&self) -> usize {
         self.data.as_ref().len()
@@ -76,6 +204,84 @@ where
     }
 }
 
+impl<C, P, D, T: Copy> Buffer<D, T, C, P>
+where
+    D: AsRef<[T]>,
+{
+    /// A source over `range` of the available area, borrowing it without copying.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidInput`] if `range` is not within
+    /// `0..available()`.
+    pub fn sub_source(&self, range: Range<usize>) -> IO<Buffer<&[T], T, SCopy, SCopy>> {
+        let sub = self.as_read().get(range.clone()).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("range {range:?} out of available 0..{}", self.available()),
+            )
+        })?;
+        Ok(Buffer::from_copy(sub).as_source())
+    }
+}
+
+impl<C, P, D, T: PartialEq> Buffer<D, T, C, P>
+where
+    D: AsRef<[T]>,
+{
+    pub fn starts_with(&self, prefix: &[T]) -> bool {
+        self.as_read().starts_with(prefix)
+    }
+    pub fn ends_with(&self, suffix: &[T]) -> bool {
+        self.as_read().ends_with(suffix)
+    }
+    /// The offset of the first occurrence of `needle` in the available area, by naive search.
+    pub fn find(&self, needle: &[T]) -> Option<usize> {
+        if needle.is_empty() {
+            return Some(0);
+        }
+        self.as_read()
+            .windows(needle.len())
+            .position(|window| window == needle)
+    }
+}
+
+impl<C, P, D> Buffer<D, u8, C, P>
+where
+    D: AsRef<[u8]>,
+{
+    /// The available bytes as UTF-8, or [`io::ErrorKind::InvalidData`].
+    pub fn as_str(&self) -> IO<&str> {
+        std::str::from_utf8(self.as_read())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// The 32-bit FNV-1a hash of the available bytes, without consuming them.
+    ///
+    /// Fast and stable across versions, for quick integrity comparisons; it is not
+    /// cryptographic.
+    pub fn checksum(&self) -> u32 {
+        self.as_read().iter().fold(0x811c_9dc5, |hash, &b| {
+            (hash ^ b as u32).wrapping_mul(0x0100_0193)
+        })
+    }
+
+    /// Consumes and returns the longest UTF-8 prefix of the available bytes.
+    ///
+    /// A multi-byte character cut short at the end of the available area is left in the
+    /// buffer, to be completed by a subsequent read. Invalid UTF-8 is an
+    /// [`io::ErrorKind::InvalidData`] error.
+    pub fn consume_str(&mut self) -> IO<&str> {
+        let valid = match std::str::from_utf8(self.as_read()) {
+            Ok(str) => str.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+        };
+        let start = self.span.0;
+        self.span.0 += valid;
+        std::str::from_utf8(&self.data.as_ref()[start..start + valid])
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
 impl<C, P, D, T> Buffer<D, T, C, P>
 where
     D: AsMut<[T]>,
@@ -91,6 +297,19 @@ where
     pub fn read(&mut self, mut from: impl Source<T>) -> IO {
         from.source(self.as_write()).tap_ok(|n| self.span.1 += n)
     }
+    /// Reads once from each of `sources` in turn (gather), until the free area is filled.
+    ///
+    /// Returns the total read.
+    pub fn read_vectored<S: Source<T>>(&mut self, sources: &mut [S]) -> IO {
+        let mut total = 0;
+        for source in sources {
+            if self.as_write().is_empty() {
+                break;
+            }
+            total += self.read(source)?;
+        }
+        Ok(total)
+    }
 }
 
 impl<C: CopyStrategy<T>, P, D, T> Buffer<D, T, C, P> {
@@ -112,6 +331,11 @@ where
         self.span.0 += n;
         Ok(n)
     }
+    /// Borrows the available items as a [`Source`], for handing to flow combinators
+    /// without moving the buffer; whatever gets sourced from it is consumed.
+    pub fn reader(&mut self) -> impl Source<T> + '_ {
+        self
+    }
 }
 
 impl<C: CopyStrategy<T>, P, D, T> Buffer<D, T, C, P>
@@ -135,17 +359,436 @@ where
             data,
             ..
         } = self;
+        #[cfg(feature = "metrics")]
+        if *start > 0 && *end > *start {
+            self.compaction_stats.0 += 1;
+            self.compaction_stats.1 += *end - *start;
+        }
         P::compact_within(data.as_mut(), *start..*end);
         *end -= *start;
         *start = 0;
     }
 }
 
+impl<C, P: CompactStrategy<T>, D, T> Buffer<D, T, C, P>
+where
+    D: AsMut<[T]> + AsRef<[T]>,
+{
+    /// Reads from `source` until at least `n` items are available, compacting if the free
+    /// area runs out.
+    ///
+    /// Returns whether `n` items became available; `false` means the source got depleted
+    /// first, or `n` exceeds the buffer's length.
+    pub fn ensure_available(&mut self, mut source: impl Source<T>, n: usize) -> IO<bool> {
+        while self.available() < n {
+            if self.is_full() {
+                self.compact();
+                if self.is_full() {
+                    break;
+                }
+            }
+            if self.read(&mut source)? == 0 {
+                break;
+            }
+        }
+        Ok(self.available() >= n)
+    }
+}
+
+impl<C, P, T> Buffer<Vec<T>, T, C, P> {
+    /// Reserves room for at least `additional` more items in the backing `Vec`, so that
+    /// growing the buffer by as much (e.g. with [`Self::free_mut()`]) does not reallocate.
+    ///
+    /// Allocation failure is an [`io::ErrorKind::OutOfMemory`] error, instead of an abort.
+    pub fn try_reserve(&mut self, additional: usize) -> IO<()> {
+        self.data
+            .try_reserve(additional)
+            .map_err(|e| io::Error::new(io::ErrorKind::OutOfMemory, e))
+    }
+}
+
+impl<C, P: CompactStrategy<T>, T> Buffer<Vec<T>, T, C, P> {
+    /// Moves the available items from the `n`th on into a new buffer, to be drained on its
+    /// own, leaving the first `n` in this one.
+    ///
+    /// The buffer is compacted first, and its free area is dropped; `n` past the available
+    /// items splits off an empty buffer.
+    pub fn split_off(&mut self, n: usize) -> Self {
+        self.compact();
+        let n = std::cmp::min(n, self.available());
+        self.data.truncate(self.span.1);
+        let tail = self.data.split_off(n);
+        self.span.1 = n;
+
+        let mut other = Self::new(tail);
+        other.span.1 = other.data.len();
+        other
+    }
+}
+
+impl<C, P: CompactStrategy<T>, T: Default> Buffer<Vec<T>, T, C, P> {
+    /// The free area, made at least `at_least` items long by compacting, and if that is not
+    /// enough, by growing the backing `Vec` with default items.
+    pub fn free_mut(&mut self, at_least: usize) -> &mut [T] {
+        if self.free() < at_least {
+            self.compact();
+        }
+        if self.free() < at_least {
+            let len = self.span.1 + at_least;
+            self.data.resize_with(len, T::default);
+        }
+        self.as_write()
+    }
+}
+
+impl<C: CopyStrategy<T>, P: CompactStrategy<T>, D, T> Transfusable<T> for Buffer<D, T, C, P>
+where
+    D: AsMut<[T]> + AsRef<[T]>,
+{
+    fn compact(&mut self) {
+        Buffer::compact(self)
+    }
+    fn is_empty(&self) -> bool {
+        Buffer::is_empty(self)
+    }
+    fn is_full(&self) -> bool {
+        Buffer::is_full(self)
+    }
+    fn read(&mut self, source: impl Source<T>) -> IO {
+        Buffer::read(self, source)
+    }
+    fn write(&mut self, sink: impl Sink<T>) -> IO {
+        Buffer::write(self, sink)
+    }
+}
+
 impl<C: CopyStrategy<T>, P: CompactStrategy<T>, D, T> Buffer<D, T, C, P>
 where
     D: AsMut<[T]> + AsRef<[T]>,
 {
+    /// Moves items from `source` to `sink` through the buffer, until neither makes progress,
+    /// returning the total written.
+    ///
+    /// The first `Ok(0)` from the source is taken as final, and it is not read from again.
+    /// A full buffer is not read into at all, so back-pressure from the sink is never
+    /// mistaken for that: a transfusion stopped by a sink accepting nothing leaves the
+    /// buffer full, and calling it again once the sink drains carries on with the source.
     pub fn transfuse(&mut self, source: impl Source<T>, sink: impl Sink<T>) -> IO {
-        transfuse_rec(false, 0, self, source, sink)
+        transfuse_from(false, 0, self, source, sink)
+    }
+
+    /// Like [`Self::transfuse()`], but consumes the buffer, and hands back the total, the
+    /// source, the sink, and the items left in the buffer.
+    pub fn transfuse_complete<S, K>(
+        mut self,
+        mut source: S,
+        mut sink: K,
+    ) -> IO<TransfuseResult<T, S, K>>
+    where
+        S: Source<T>,
+        K: Sink<T>,
+        T: Clone,
+    {
+        let total = self.transfuse(&mut source, &mut sink)?;
+        Ok(TransfuseResult {
+            total,
+            source,
+            sink,
+            leftover: self.as_read().to_vec(),
+        })
+    }
+
+    /// Transfuses each of `sources` in turn into `sink`, returning the total written.
+    pub fn transfuse_all<S: Source<T>>(
+        &mut self,
+        sources: impl IntoIterator<Item = S>,
+        mut sink: impl Sink<T>,
+    ) -> IO {
+        let mut total = 0usize;
+        for source in sources {
+            total = total.saturating_add(self.transfuse(source, &mut sink)?);
+        }
+        Ok(total)
+    }
+
+    /// Writes the whole of `header` to `sink`, then transfuses `source` into it.
+    ///
+    /// Short header writes are retried; a sink refusing the rest of the header with
+    /// `Ok(0)` fails with [`io::ErrorKind::WriteZero`]. The returned total includes the
+    /// header.
+    pub fn transfuse_with_header(
+        &mut self,
+        header: &[T],
+        source: impl Source<T>,
+        mut sink: impl Sink<T>,
+    ) -> IO {
+        let mut written = 0;
+        while written < header.len() {
+            match sink.sink(&header[written..])? {
+                0 => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "sink did not accept the whole header",
+                    ))
+                }
+                n => written += n,
+            }
+        }
+        Ok(written + self.transfuse(source, sink)?)
+    }
+
+    /// Runs at most `cycles` compact/read/write cycles of [`Self::transfuse()`], leaving
+    /// the buffer, source and sink in their intermediate state.
+    ///
+    /// Stops early if a cycle makes no progress, like `transfuse()` would.
+    pub fn transfuse_n_cycles(
+        &mut self,
+        source: impl Source<T>,
+        sink: impl Sink<T>,
+        cycles: usize,
+    ) -> IO {
+        Ok(self.transfuse_cycles(source, sink, cycles)?.1)
+    }
+
+    /// Like [`Self::transfuse()`], returning the totals read from the source and written
+    /// to the sink.
+    ///
+    /// The two differ by what is left in the buffer, when the sink stops accepting
+    /// before the source is depleted.
+    pub fn transfuse_accounting(
+        &mut self,
+        source: impl Source<T>,
+        sink: impl Sink<T>,
+    ) -> IO<(usize, usize)> {
+        self.transfuse_cycles(source, sink, usize::MAX)
+    }
+
+    /// Like [`Self::transfuse()`], calling `hook` with the buffer on each cycle, between
+    /// compacting and reading, to inspect or adjust it.
+    pub fn transfuse_with_hook<F: FnMut(&mut Self)>(
+        &mut self,
+        mut source: impl Source<T>,
+        mut sink: impl Sink<T>,
+        mut hook: F,
+    ) -> IO {
+        let mut transfusion = Transfusion::default();
+        loop {
+            self.compact();
+            hook(self);
+            let read = transfusion.read(self, &mut source)?;
+            let write = transfusion.write(self, &mut sink)?;
+            if read == 0 && write == 0 {
+                return Ok(transfusion.written);
+            }
+        }
+    }
+
+    /// Like [`Self::transfuse()`], but stops at the first item for which `is_sentinel`
+    /// holds, once everything before it has been written, leaving it and whatever was read
+    /// after it in the buffer.
+    ///
+    /// The sentinel is not read past: if found, it is at the front of [`Self::as_read()`].
+    pub fn transfuse_until_item<F: FnMut(&T) -> bool>(
+        &mut self,
+        mut source: impl Source<T>,
+        mut sink: impl Sink<T>,
+        mut is_sentinel: F,
+    ) -> IO {
+        let mut transfusion = Transfusion::default();
+        // Available items already checked, and where among them the sentinel is.
+        let mut scanned = 0;
+        let mut sentinel = None;
+        loop {
+            self.compact();
+            let read = if sentinel.is_some() {
+                0
+            } else {
+                transfusion.read(self, &mut source)?
+            };
+            if sentinel.is_none() {
+                sentinel = self.as_read()[scanned..]
+                    .iter()
+                    .position(&mut is_sentinel)
+                    .map(|at| scanned + at);
+                scanned = self.available();
+            }
+
+            let ready = sentinel.unwrap_or(scanned);
+            let write = if ready == 0 {
+                0
+            } else {
+                sink.sink(&self.as_read()[..ready])?
+            };
+            self.span.0 += write;
+            scanned -= write;
+            sentinel = sentinel.map(|at| at - write);
+            transfusion.wrote(write);
+
+            if sentinel == Some(0) || (read == 0 && write == 0) {
+                return Ok(transfusion.written);
+            }
+        }
+    }
+
+    /// Like [`Self::transfuse()`], but each cycle drains the buffer to the sink as far as
+    /// it accepts, before reading from the source again.
+    ///
+    /// Items linger in the buffer less, at the cost of more sink calls; the default order
+    /// instead tops the buffer up after every write, favouring fewer, larger writes.
+    pub fn transfuse_drain_first(
+        &mut self,
+        mut source: impl Source<T>,
+        mut sink: impl Sink<T>,
+    ) -> IO {
+        let mut transfusion = Transfusion::default();
+        loop {
+            let mut write = 0usize;
+            loop {
+                match transfusion.write(self, &mut sink)? {
+                    0 => break,
+                    n => write += n,
+                }
+            }
+            self.compact();
+            let read = transfusion.read(self, &mut source)?;
+            if read == 0 && write == 0 {
+                return Ok(transfusion.written);
+            }
+        }
+    }
+
+    /// Like [`Self::transfuse()`], but checks the time before each cycle, and stops once
+    /// `deadline` has passed, leaving pending items in the buffer.
+    ///
+    /// Returns the total written, and whether the deadline cut the transfusion short.
+    pub fn transfuse_deadline(
+        &mut self,
+        mut source: impl Source<T>,
+        mut sink: impl Sink<T>,
+        deadline: Instant,
+    ) -> IO<(usize, bool)> {
+        let mut transfusion = Transfusion::default();
+        loop {
+            if Instant::now() >= deadline {
+                return Ok((transfusion.written, true));
+            }
+            if !transfusion.cycle(self, &mut source, &mut sink)? {
+                return Ok((transfusion.written, false));
+            }
+        }
+    }
+
+    /// Like [`Self::transfuse()`], applying `f` to each newly read region of the buffer
+    /// before writing it, for same-type transforms that need no second buffer.
+    pub fn transfuse_inplace<F: FnMut(&mut [T])>(
+        &mut self,
+        mut source: impl Source<T>,
+        mut f: F,
+        mut sink: impl Sink<T>,
+    ) -> IO {
+        let mut transfusion = Transfusion::default();
+        loop {
+            self.compact();
+            let read = transfusion.read(self, &mut source)?;
+            if read > 0 {
+                let end = self.span.1;
+                f(&mut self.data.as_mut()[end - read..end]);
+            }
+            let write = transfusion.write(self, &mut sink)?;
+            if read == 0 && write == 0 {
+                return Ok(transfusion.written);
+            }
+        }
+    }
+
+    /// Like [`Self::transfuse()`], passing the items through `map` on their way from the
+    /// source to the sink, by way of a scratch buffer of `U` as long as this one.
+    ///
+    /// `map` is handed the available items and the scratch buffer, and returns how many
+    /// leading items it mapped one-to-one. Items leave this buffer only once the sink has
+    /// accepted them mapped: the rest stay in it, unmapped, as with `transfuse()`, and are
+    /// handed to `map` again, along with more read items, on the next cycle (or call).
+    /// `map` should hence not depend on seeing each item only once.
+    pub fn transfuse_map<U, F>(
+        &mut self,
+        mut source: impl Source<T>,
+        mut map: F,
+        mut sink: impl Sink<U>,
+    ) -> IO
+    where
+        U: Copy + Default,
+        F: FnMut(&[T], &mut [U]) -> IO,
+    {
+        let mut mapped = vec![U::default(); self.len()];
+        let mut transfusion = Transfusion::default();
+        loop {
+            self.compact();
+            let read = transfusion.read(self, &mut source)?;
+            let from = self.as_read();
+            let n = if from.is_empty() {
+                0
+            } else {
+                map(from, &mut mapped[..from.len()])?
+            };
+            if n > from.len() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "map reported more items than it was given",
+                ));
+            }
+            let write = if n == 0 { 0 } else { sink.sink(&mapped[..n])? };
+            self.span.0 += write;
+            transfusion.wrote(write);
+
+            if read == 0 && write == 0 {
+                return Ok(transfusion.written);
+            }
+        }
+    }
+
+    /// Like [`Self::transfuse()`], but when the sink stops accepting (`Ok(0)` or
+    /// [`io::ErrorKind::WouldBlock`]) while data is pending, sleeps for `backoff` and
+    /// retries, instead of giving up.
+    ///
+    /// Returns only once the source is depleted and the buffer is drained into the sink;
+    /// a sink that never accepts again blocks this forever.
+    pub fn transfuse_blocking(
+        &mut self,
+        mut source: impl Source<T>,
+        mut sink: impl Sink<T>,
+        backoff: Duration,
+    ) -> IO {
+        let mut transfusion = Transfusion::default();
+        loop {
+            self.compact();
+            let read = transfusion.read(self, &mut source)?;
+            let write = match transfusion.write(self, &mut sink) {
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => 0,
+                write => write?,
+            };
+
+            if read == 0 && write == 0 {
+                if self.is_empty() {
+                    // An empty buffer can only be stuck on a depleted source.
+                    return Ok(transfusion.written);
+                }
+                std::thread::sleep(backoff);
+            }
+        }
+    }
+
+    fn transfuse_cycles(
+        &mut self,
+        mut source: impl Source<T>,
+        mut sink: impl Sink<T>,
+        cycles: usize,
+    ) -> IO<(usize, usize)> {
+        let mut transfusion = Transfusion::default();
+        for _ in 0..cycles {
+            if !transfusion.cycle(self, &mut source, &mut sink)? {
+                break;
+            }
+        }
+        Ok((transfusion.read, transfusion.written))
     }
 }
@@ -8,8 +8,6 @@ impl<T: Copy> CopyStrategy<T> for SCopy {
 
 impl<T: Clone> CopyStrategy<T> for SClone {
     fn copy_slice(dest: &mut [T], src: &[T]) {
-        for i in 0..src.len() {
-            dest[i] = src[i].clone();
-        }
+        dest[..src.len()].clone_from_slice(src);
     }
 }
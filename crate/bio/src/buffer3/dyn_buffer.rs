@@ -0,0 +1,184 @@
+use super::*;
+
+impl<D, T> DynBuffer<D, T> {
+    pub fn new(data: D, copier: fn(&mut [T], &[T]), compactor: fn(&mut [T], Range<usize>)) -> Self {
+        Self {
+            data,
+            span: (0, 0),
+            copier,
+            compactor,
+        }
+    }
+
+    pub fn with_copier(mut self, copier: fn(&mut [T], &[T])) -> Self {
+        self.copier = copier;
+        self
+    }
+    pub fn with_compactor(mut self, compactor: fn(&mut [T], Range<usize>)) -> Self {
+        self.compactor = compactor;
+        self
+    }
+    pub fn set_copier(&mut self, copier: fn(&mut [T], &[T])) {
+        self.copier = copier;
+    }
+    pub fn set_compactor(&mut self, compactor: fn(&mut [T], Range<usize>)) {
+        self.compactor = compactor;
+    }
+
+    pub fn available(&self) -> usize {
+        let &Self {
+            span: (start, end), ..
+        } = self;
+        end - start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.available() == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.span = (0, 0);
+    }
+}
+
+impl<D, T: Clone> DynBuffer<D, T> {
+    pub fn from_clone(data: D) -> Self {
+        Self::new(
+            data,
+            <SClone as CopyStrategy<T>>::copy_slice,
+            <SNone as CompactStrategy<T>>::compact_within,
+        )
+    }
+}
+
+impl<D, T: Copy> DynBuffer<D, T> {
+    pub fn from_copy(data: D) -> Self {
+        Self::new(
+            data,
+            <SCopy as CopyStrategy<T>>::copy_slice,
+            <SCopy as CompactStrategy<T>>::compact_within,
+        )
+    }
+}
+
+impl<D, T> DynBuffer<D, T>
+where
+    D: AsRef<[T]>,
+{
+    pub fn as_source(mut self) -> Self {
+        self.span = (0, self.data.as_ref().len());
+        self
+    }
+    pub fn as_read(&self) -> &[T] {
+        let Self {
+            data,
+            span: (start, end),
+            ..
+        } = self;
+        &data.as_ref()[*start..*end]
+    }
+    pub fn write(&mut self, mut into: impl Sink<T>) -> IO {
+        into.sink(self.as_read()).tap_ok(|n| self.span.0 += n)
+    }
+    pub fn copy_into(&mut self, into: &mut [T]) -> IO {
+        let n = std::cmp::min(into.len(), self.available());
+        (self.copier)(&mut into[..n], &self.as_read()[..n]);
+        self.span.0 += n;
+        Ok(n)
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.as_ref().len()
+    }
+    pub fn free(&self) -> usize {
+        self.len() - self.span.1
+    }
+    pub fn is_full(&self) -> bool {
+        self.free() == 0
+    }
+}
+
+impl<D, T> DynBuffer<D, T>
+where
+    D: AsMut<[T]>,
+{
+    pub fn as_write(&mut self) -> &mut [T] {
+        let Self {
+            data,
+            span: (_, end),
+            ..
+        } = self;
+        &mut data.as_mut()[*end..]
+    }
+    pub fn read(&mut self, mut from: impl Source<T>) -> IO {
+        from.source(self.as_write()).tap_ok(|n| self.span.1 += n)
+    }
+    pub fn copy_from(&mut self, from: &[T]) -> IO {
+        let copier = self.copier;
+        let into = self.as_write();
+        let n = std::cmp::min(into.len(), from.len());
+        copier(&mut into[..n], &from[..n]);
+        self.span.1 += n;
+        Ok(n)
+    }
+    pub fn compact(&mut self) {
+        let Self {
+            span: (start, end),
+            data,
+            compactor,
+            ..
+        } = self;
+        compactor(data.as_mut(), *start..*end);
+        *end -= *start;
+        *start = 0;
+    }
+}
+
+impl<D, T> DynBuffer<D, T>
+where
+    D: AsMut<[T]> + AsRef<[T]>,
+{
+    /// Same contract as [`Buffer::transfuse`].
+    pub fn transfuse(&mut self, source: impl Source<T>, sink: impl Sink<T>) -> IO {
+        transfuse_from(false, 0, self, source, sink)
+    }
+}
+
+impl<D, T> Transfusable<T> for DynBuffer<D, T>
+where
+    D: AsMut<[T]> + AsRef<[T]>,
+{
+    fn compact(&mut self) {
+        DynBuffer::compact(self)
+    }
+    fn is_empty(&self) -> bool {
+        DynBuffer::is_empty(self)
+    }
+    fn is_full(&self) -> bool {
+        DynBuffer::is_full(self)
+    }
+    fn read(&mut self, source: impl Source<T>) -> IO {
+        DynBuffer::read(self, source)
+    }
+    fn write(&mut self, sink: impl Sink<T>) -> IO {
+        DynBuffer::write(self, sink)
+    }
+}
+
+impl<D, T> Source<T> for DynBuffer<D, T>
+where
+    D: AsRef<[T]>,
+{
+    fn source(&mut self, into: &mut [T]) -> IO {
+        self.copy_into(into)
+    }
+}
+
+impl<D, T> Sink<T> for DynBuffer<D, T>
+where
+    D: AsMut<[T]>,
+{
+    fn sink(&mut self, from: &[T]) -> IO {
+        self.copy_from(from)
+    }
+}
@@ -1,6 +1,464 @@
-use super::*;
+use {
+    super::*,
+    std::{
+        cell::RefCell,
+        collections::VecDeque,
+        rc::Rc,
+    },
+};
 
 pub struct EachConsecutiveFlow<D, T, C, P>(pub Buffer<D, T, C, P>);
 pub struct EachConsecutive<S, D, T, C, P>(S, Option<Buffer<D, T, C, P>>);
 
+/// Folds each disjoint window of `W` items into a single `A`, starting from `init`.
+///
+/// When `emit_partial` is set, a trailing window of less than `W` items is also emitted
+/// on source depletion; otherwise it is dropped.
+pub struct ReduceWindowsFlow<const W: usize, A, F> {
+    pub init: A,
+    pub reduce: F,
+    pub emit_partial: bool,
+}
+pub struct ReduceWindows<const W: usize, S, T, A, F> {
+    source: S,
+    scratch: [T; W],
+    init: A,
+    reduce: F,
+    emit_partial: bool,
+    acc: Option<A>,
+    count: usize,
+    done: bool,
+}
+
+/// Passes items through unchanged, while also writing them to the side sink `.0`.
+///
+/// Every item passed through has been accepted by the side sink: short writes to it are
+/// retried, and a side sink accepting nothing (`Ok(0)`) fails the read with
+/// [`io::ErrorKind::WriteZero`], so that the side sink never silently falls behind.
+pub struct TeeToFlow<K>(pub K);
+pub struct TeeTo<S, K> {
+    source: S,
+    side: K,
+}
+
+/// Unwraps a source of `Result`s, surfacing the first `Err` as an [`io::Error`].
+///
+/// The `Ok` items preceding an `Err` are delivered first; the next read then fails with
+/// the error, which is thereby consumed. Items after it remain available to later reads.
+pub struct TryUnwrapFlow;
+pub struct TryUnwrap<S, T, E> {
+    source: S,
+    scratch: Vec<Result<T, E>>,
+    pending: VecDeque<Result<T, E>>,
+}
+
+/// Returns `Ok(0)` once after every `.0` items produced, yielding control to cooperative
+/// schedulers. See [`Yielding::is_done()`] to tell a yield from the end of the source.
+pub struct YieldingFlow(pub usize);
+pub struct Yielding<S> {
+    source: S,
+    every: usize,
+    produced: usize,
+    done: bool,
+}
+
+/// Emits the difference of each value from the previous one; the first value passes
+/// through. Differences wrap around on overflow, and [`UnDeltaFlow`] reverses them.
+pub struct DeltaFlow;
+pub struct Delta<S> {
+    source: S,
+    prev: i64,
+}
+/// Reconstructs absolute values from [`DeltaFlow`] differences, by running sum.
+pub struct UnDeltaFlow;
+pub struct UnDelta<S> {
+    source: S,
+    acc: i64,
+}
+
+/// Splits a source of interleaved frames of `N` channels into `N` sources, one per
+/// channel; see [`Deinterleave::split()`].
+///
+/// The channels can be pulled independently, in any order. Items read from the shared
+/// source for the other channels are queued until those channels are pulled, so a
+/// channel that lags behind (or is never pulled) makes its queue grow unboundedly.
+pub struct Deinterleave<const N: usize>;
+pub struct DeinterleaveChannel<const N: usize, S, T> {
+    shared: Rc<RefCell<Deinterleaved<N, S, T>>>,
+    channel: usize,
+}
+struct Deinterleaved<const N: usize, S, T> {
+    source: S,
+    queues: [VecDeque<T>; N],
+    next: usize,
+    done: bool,
+}
+
+/// Convolves a stream of samples with the `taps` coefficients of a finite impulse response
+/// filter.
+///
+/// The filter warms up over the first `taps.len() - 1` samples, which produce no output;
+/// after that, every input sample produces one output sample.
+#[derive(Clone, Debug)]
+pub struct FirFlow {
+    pub taps: Vec<f64>,
+}
+pub struct Fir<S> {
+    source: S,
+    taps: Vec<f64>,
+    history: VecDeque<f64>,
+}
+
+/// Splits a byte stream of a top-level JSON array into the raw bytes of its elements,
+/// one buffer per element, reading `.0` bytes at a time.
+///
+/// Only the structure is checked, by tracking string and bracket nesting: element
+/// contents are passed through as they are, without surrounding whitespace. Malformed
+/// structure is an [`io::ErrorKind::InvalidData`] error, and a stream ending before the
+/// array is closed is an [`io::ErrorKind::UnexpectedEof`] error.
+pub struct JsonArrayItemsFlow(pub usize);
+pub struct JsonArrayItems<S> {
+    source: S,
+    buf: Buffer<Vec<u8>, u8, SCopy, SCopy>,
+    scan: JsonScan,
+}
+#[derive(Default)]
+struct JsonScan {
+    stage: JsonStage,
+    closers: Vec<u8>,
+    in_string: bool,
+    escaped: bool,
+    item: Vec<u8>,
+}
+#[derive(Default, PartialEq)]
+enum JsonStage {
+    #[default]
+    Open,
+    First,
+    Before,
+    Item,
+    Closed,
+}
+
+/// Compresses a byte stream into LZ77 [`Token`]s, matching repeated runs against the
+/// last `window` bytes.
+///
+/// Matches are found by brute-force search, may overlap the bytes they reproduce, and
+/// are at least 3 and at most 255 bytes long; anything shorter is emitted as literals.
+pub struct Lz77Flow {
+    pub window: usize,
+}
+pub struct Lz77<S> {
+    source: S,
+    window: usize,
+    history: VecDeque<u8>,
+    lookahead: VecDeque<u8>,
+    done: bool,
+}
+/// An LZ77 token: a literal byte, or a copy of `length` bytes starting `offset` bytes
+/// back.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Token {
+    Literal(u8),
+    Match { offset: usize, length: usize },
+}
+/// Decompresses [`Lz77Flow`] tokens back into bytes, keeping the last `window` bytes.
+///
+/// A match reaching further back than the window, or than the bytes decompressed so
+/// far, or shorter than [`Lz77Flow`] ever emits, is an [`io::ErrorKind::InvalidData`]
+/// error.
+pub struct UnLz77Flow {
+    pub window: usize,
+}
+pub struct UnLz77<S> {
+    source: S,
+    window: usize,
+    history: VecDeque<u8>,
+    pending: Option<(usize, usize)>,
+}
+
+/// Renders a byte stream as hex dump text, `width` bytes per line: the offset, the bytes
+/// in hex, and their printable ASCII, with `.` standing in for the rest.
+///
+/// ```text
+/// 00000000  48 65 6c 6c 6f 2c 20 68  |Hello, h|
+/// ```
+pub struct HexDumpFlow {
+    pub width: usize,
+}
+pub struct HexDump<S> {
+    source: S,
+    width: usize,
+    offset: usize,
+    line: Vec<u8>,
+    text: VecDeque<u8>,
+    done: bool,
+}
+
+/// Batches a chatty source's reads, so that each read passes through at least `MIN`
+/// items, unless the destination is shorter or the source got depleted.
+///
+/// With a `MIN` of 0 (or 1), reads pass straight through.
+pub struct CoalesceFlow<const MIN: usize>;
+pub struct Coalesce<const MIN: usize, S> {
+    source: S,
+    done: bool,
+}
+
+/// Verifies and strips a leading `magic` header, passing the rest through unchanged; the
+/// read side counterpart of [`Buffer::transfuse_with_header()`].
+///
+/// A mismatching header is an [`io::ErrorKind::InvalidData`] error, and a stream ending
+/// within the header an [`io::ErrorKind::UnexpectedEof`] one.
+pub struct StripMagicFlow {
+    pub magic: Vec<u8>,
+}
+pub struct StripMagic<S> {
+    source: S,
+    magic: Vec<u8>,
+    matched: usize,
+}
+
+/// Appends an XOR parity byte after every `BLOCK` bytes, and after a final partial
+/// block, so that a single lost or corrupted byte per block can be detected downstream.
+pub struct ParityFlow<const BLOCK: usize>;
+pub struct Parity<const BLOCK: usize, S> {
+    source: S,
+    filled: usize,
+    parity: u8,
+    pending: Option<u8>,
+    done: bool,
+}
+/// Verifies and strips the parity bytes of [`ParityFlow`].
+///
+/// A block not matching its parity byte is an [`io::ErrorKind::InvalidData`] error.
+pub struct CheckParityFlow<const BLOCK: usize>;
+pub struct CheckParity<const BLOCK: usize, S> {
+    source: S,
+    block: Vec<u8>,
+    verified: VecDeque<u8>,
+    done: bool,
+}
+
+/// Keeps up to `N` upcoming items buffered, so that they can be inspected with
+/// [`Lookahead::peek_window()`] before being read.
+pub struct LookaheadFlow<const N: usize>;
+pub struct Lookahead<const N: usize, S, T> {
+    source: S,
+    window: Buffer<Vec<T>, T, SCopy, SCopy>,
+    done: bool,
+}
+
+/// Prefixes each line of a byte stream with its number and a tab, counting from `start`,
+/// like `cat -n`.
+pub struct NumberLinesFlow {
+    pub start: usize,
+}
+pub struct NumberLines<S> {
+    source: S,
+    line: usize,
+    at_line_start: bool,
+    pending: VecDeque<u8>,
+}
+
+/// Frames a byte stream into messages ending with the (possibly multi-byte) `term`,
+/// emitting each message without its terminator.
+///
+/// A trailing message missing its terminator is emitted as is, unless `strict` is set,
+/// in which case it is an [`io::ErrorKind::UnexpectedEof`] error.
+pub struct UntilTerminatorFlow {
+    pub term: Vec<u8>,
+    pub strict: bool,
+}
+pub struct UntilTerminator<S> {
+    source: S,
+    term: Vec<u8>,
+    strict: bool,
+    buf: Buffer<Vec<u8>, u8, SCopy, SCopy>,
+    message: Vec<u8>,
+    done: bool,
+}
+
+/// Yields the available items of each upstream buffer in turn; the inverse of
+/// [`EachConsecutiveFlow`].
+pub struct FlattenFlow;
+pub struct Flatten<S, D, T, C, P> {
+    source: S,
+    current: Buffer<D, T, C, P>,
+    done: bool,
+}
+
+/// Decodes unsigned LEB128 varints, as used by protobuf, into `u64`s.
+///
+/// A varint not fitting in 64 bits is an [`io::ErrorKind::InvalidData`] error, and a
+/// stream ending within a varint an [`io::ErrorKind::UnexpectedEof`] one.
+pub struct VarintFlow;
+pub struct Varint<S> {
+    source: S,
+    acc: u64,
+    shift: u32,
+}
+/// Encodes `u64`s as unsigned LEB128 varints; the inverse of [`VarintFlow`].
+pub struct VarintEncodeFlow;
+pub struct VarintEncode<S> {
+    source: S,
+    pending: VecDeque<u8>,
+}
+
+/// Encodes the source as a single frame of Consistent Overhead Byte Stuffing: the bytes
+/// come out free of zeros, at an overhead of a byte per 254, followed by a `0x00`
+/// delimiting the frame.
+pub struct CobsEncodeFlow;
+pub struct CobsEncode<S> {
+    source: S,
+    block: Vec<u8>,
+    after_full: bool,
+    pending: VecDeque<u8>,
+    done: bool,
+}
+/// Decodes `0x00`-delimited COBS frames; the inverse of [`CobsEncodeFlow`].
+///
+/// The payloads of consecutive frames are concatenated. A zero within a block is an
+/// [`io::ErrorKind::InvalidData`] error, and a stream ending within a frame an
+/// [`io::ErrorKind::UnexpectedEof`] one.
+pub struct CobsDecodeFlow;
+pub struct CobsDecode<S> {
+    source: S,
+    left: u8,
+    implies_zero: bool,
+    zero_pending: bool,
+    in_frame: bool,
+}
+
+/// Compresses the source into a raw DEFLATE stream at `level` (0–9), finishing it once the
+/// source is depleted.
+#[cfg(feature = "flate2")]
+pub struct DeflateFlow {
+    pub level: u32,
+}
+#[cfg(feature = "flate2")]
+pub struct Deflate<S> {
+    source: S,
+    input: Buffer<Vec<u8>, u8, SCopy, SCopy>,
+    codec: flate2::Compress,
+    source_done: bool,
+    finished: bool,
+}
+/// Decompresses a raw DEFLATE stream; the inverse of [`DeflateFlow`].
+///
+/// Corrupt input is an [`io::ErrorKind::InvalidData`] error, and the source ending before
+/// the stream does an [`io::ErrorKind::UnexpectedEof`] one. Anything after the end of the
+/// stream is left unread.
+#[cfg(feature = "flate2")]
+pub struct InflateFlow;
+#[cfg(feature = "flate2")]
+pub struct Inflate<S> {
+    source: S,
+    input: Buffer<Vec<u8>, u8, SCopy, SCopy>,
+    codec: flate2::Decompress,
+    source_done: bool,
+    finished: bool,
+}
+
+/// Splits a CSV byte stream into its fields, reading 1 KiB at a time.
+///
+/// Fields may be quoted with `quote`, making delimiters and newlines within them literal,
+/// and a doubled `quote` within a quoted field stands for a single one. Records end at
+/// `\n`; `\r` outside quotes is dropped, so `\r\n` works too. An unterminated quoted field
+/// is an [`io::ErrorKind::InvalidData`] error.
+pub struct CsvFieldsFlow {
+    pub delimiter: u8,
+    pub quote: u8,
+}
+pub struct CsvFields<S> {
+    source: S,
+    buf: Buffer<Vec<u8>, u8, SCopy, SCopy>,
+    scan: CsvScan,
+}
+/// A field of [`CsvFieldsFlow`], unquoted.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CsvField {
+    pub data: Buffer<Vec<u8>, u8, SCopy, SCopy>,
+    /// Whether this is the last field of its record.
+    pub end_of_record: bool,
+}
+struct CsvScan {
+    delimiter: u8,
+    quote: u8,
+    field: Vec<u8>,
+    in_record: bool,
+    quoted: bool,
+    in_quotes: bool,
+    quote_pending: bool,
+}
+
+/// Forwards only the items for which `pred` holds, reading `capacity` items at a time.
+pub struct FilterFlow<P> {
+    pub pred: P,
+    /// Must not be 0, or creating the [`Filter`] panics: nothing could ever be read.
+    pub capacity: usize,
+}
+pub struct Filter<S, P, T> {
+    source: S,
+    pred: P,
+    buf: Buffer<Vec<T>, T, SCopy, SCopy>,
+    done: bool,
+}
+
+/// Sources all of `A`, then all of `B`; a single read may span both.
+pub struct Chain<A, B> {
+    a: A,
+    b: B,
+    a_done: bool,
+}
+
+/// Pairs each item with its index in the source, counting from 0.
+pub struct EnumerateFlow;
+pub struct Enumerate<S, T> {
+    source: S,
+    next: usize,
+    scratch: Vec<T>,
+}
+
+pub struct SkipFlow(pub usize);
+pub struct Skip<S, T> {
+    source: S,
+    remaining: usize,
+    scratch: Buffer<Vec<T>, T, SCopy, SCopy>,
+    done: bool,
+}
+
+pub struct TakeFlow(pub usize);
+pub struct Take<S> {
+    source: S,
+    remaining: usize,
+}
+
+mod chain;
+mod coalesce;
+mod cobs;
+mod csv_fields;
+#[cfg(feature = "flate2")]
+mod deflate;
+mod deinterleave;
+mod delta;
 mod each_consecutive;
+mod enumerate;
+mod filter;
+mod fir;
+mod flatten;
+mod hex_dump;
+mod json_array_items;
+mod lookahead;
+mod lz77;
+mod number_lines;
+mod parity;
+mod reduce_windows;
+mod skip;
+mod strip_magic;
+mod take;
+mod tee_to;
+mod try_unwrap;
+mod until_terminator;
+mod varint;
+mod yielding;
@@ -0,0 +1,29 @@
+use super::*;
+
+impl<A, B> Chain<A, B> {
+    pub fn new(a: A, b: B) -> Self {
+        Self {
+            a,
+            b,
+            a_done: false,
+        }
+    }
+}
+
+impl<A: Source<T>, B: Source<T>, T> Source<T> for Chain<A, B> {
+    fn source(&mut self, into: &mut [T]) -> IO {
+        let mut n = 0;
+        // Keep reading `a` until it is depleted, so that the destination gets filled
+        // across the seam; `a` is not polled again after that.
+        while !self.a_done && n < into.len() {
+            match self.a.source(&mut into[n..])? {
+                0 => self.a_done = true,
+                read => n += read,
+            }
+        }
+        if n < into.len() {
+            n += self.b.source(&mut into[n..])?;
+        }
+        Ok(n)
+    }
+}
@@ -0,0 +1,33 @@
+use super::*;
+
+impl<const MIN: usize, S> Coalesce<MIN, S> {
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            done: false,
+        }
+    }
+}
+
+impl<const MIN: usize, T> Flow<T, T> for CoalesceFlow<MIN> {
+    type Source<S: Source<T>> = Coalesce<MIN, S>;
+
+    fn flow<S: Source<T>>(&self, inp: S) -> Self::Source<S> {
+        Coalesce::new(inp)
+    }
+}
+
+impl<const MIN: usize, S: Source<T>, T> Source<T> for Coalesce<MIN, S> {
+    fn source(&mut self, into: &mut [T]) -> IO {
+        // Read at least once, or a `MIN` of 0 would report depletion straight away.
+        let target = std::cmp::min(MIN.max(1), into.len());
+        let mut n = 0;
+        while n < target && !self.done {
+            match self.source.source(&mut into[n..])? {
+                0 => self.done = true,
+                read => n += read,
+            }
+        }
+        Ok(n)
+    }
+}
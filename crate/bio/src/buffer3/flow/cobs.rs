@@ -0,0 +1,147 @@
+use super::*;
+
+/// The most non-zero bytes a block holds, with a code byte of `0xff`.
+const MAX_BLOCK: usize = 254;
+
+impl<S> CobsEncode<S> {
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            block: Vec::with_capacity(MAX_BLOCK),
+            after_full: false,
+            pending: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// Moves the current block to the output, led by its code byte.
+    fn emit_block(&mut self) {
+        self.pending.push_back(self.block.len() as u8 + 1);
+        self.pending.extend(self.block.drain(..));
+    }
+}
+
+impl<S> CobsDecode<S> {
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            left: 0,
+            implies_zero: false,
+            zero_pending: false,
+            in_frame: false,
+        }
+    }
+}
+
+impl Flow<u8, u8> for CobsEncodeFlow {
+    type Source<S: Source<u8>> = CobsEncode<S>;
+
+    fn flow<S: Source<u8>>(&self, inp: S) -> Self::Source<S> {
+        CobsEncode::new(inp)
+    }
+}
+
+impl Flow<u8, u8> for CobsDecodeFlow {
+    type Source<S: Source<u8>> = CobsDecode<S>;
+
+    fn flow<S: Source<u8>>(&self, inp: S) -> Self::Source<S> {
+        CobsDecode::new(inp)
+    }
+}
+
+impl<S: Source<u8>> Source<u8> for CobsEncode<S> {
+    fn source(&mut self, into: &mut [u8]) -> IO {
+        let mut n = 0;
+        while n < into.len() {
+            if let Some(b) = self.pending.pop_front() {
+                into[n] = b;
+                n += 1;
+                continue;
+            }
+            if self.done {
+                break;
+            }
+
+            let mut chunk = [0u8; 256];
+            let read = self.source.source(&mut chunk)?;
+            if read == 0 {
+                // A full block implies no zero, so one ending the frame needs no
+                // (empty) block after it.
+                if !(self.block.is_empty() && self.after_full) {
+                    self.emit_block();
+                }
+                self.pending.push_back(0);
+                self.done = true;
+                continue;
+            }
+            for &b in &chunk[..read] {
+                self.after_full = false;
+                if b == 0 {
+                    self.emit_block();
+                    continue;
+                }
+                self.block.push(b);
+                if self.block.len() == MAX_BLOCK {
+                    self.emit_block();
+                    self.after_full = true;
+                }
+            }
+        }
+        Ok(n)
+    }
+}
+
+impl<S: Source<u8>> Source<u8> for CobsDecode<S> {
+    fn source(&mut self, into: &mut [u8]) -> IO {
+        let mut n = 0;
+        while n < into.len() {
+            // Each byte decodes to at most one, so this many cannot overflow `into`.
+            let mut chunk = [0u8; 256];
+            let want = std::cmp::min(chunk.len(), into.len() - n);
+            let read = self.source.source(&mut chunk[..want])?;
+            if read == 0 {
+                if n == 0 && self.in_frame {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "stream ended within a COBS frame",
+                    ));
+                }
+                break;
+            }
+
+            for &b in &chunk[..read] {
+                match (self.left, b) {
+                    // The frame delimiter: the last block's zero is not part of the payload.
+                    (0, 0) => (self.in_frame, self.zero_pending) = (false, false),
+                    // A code byte, starting the next block.
+                    (0, code) => {
+                        if self.zero_pending {
+                            into[n] = 0;
+                            n += 1;
+                        }
+                        self.in_frame = true;
+                        self.left = code - 1;
+                        self.implies_zero = code as usize <= MAX_BLOCK;
+                        self.zero_pending = self.left == 0 && self.implies_zero;
+                    }
+                    (_, 0) => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            "COBS frame ended within a block",
+                        ))
+                    }
+                    (_, b) => {
+                        into[n] = b;
+                        n += 1;
+                        self.left -= 1;
+                        self.zero_pending = self.left == 0 && self.implies_zero;
+                    }
+                }
+            }
+            if n > 0 {
+                break;
+            }
+        }
+        Ok(n)
+    }
+}
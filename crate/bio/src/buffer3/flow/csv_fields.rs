@@ -0,0 +1,120 @@
+use super::*;
+
+impl<S> CsvFields<S> {
+    pub fn new(source: S, delimiter: u8, quote: u8) -> Self {
+        Self {
+            source,
+            buf: Buffer::from_copy(vec![0; 1024]),
+            scan: CsvScan {
+                delimiter,
+                quote,
+                field: Vec::new(),
+                in_record: false,
+                quoted: false,
+                in_quotes: false,
+                quote_pending: false,
+            },
+        }
+    }
+}
+
+impl Flow<u8, CsvField> for CsvFieldsFlow {
+    type Source<S: Source<u8>> = CsvFields<S>;
+
+    fn flow<S: Source<u8>>(&self, inp: S) -> Self::Source<S> {
+        CsvFields::new(inp, self.delimiter, self.quote)
+    }
+}
+
+impl<S: Source<u8>> Source<CsvField> for CsvFields<S> {
+    fn source(&mut self, into: &mut [CsvField]) -> IO {
+        let Self { source, buf, scan } = self;
+
+        let mut n = 0;
+        while n < into.len() {
+            if buf.is_empty() {
+                buf.clear();
+                if buf.read(&mut *source)? == 0 {
+                    if let Some(field) = scan.finish()? {
+                        into[n] = field;
+                        n += 1;
+                    }
+                    break;
+                }
+            }
+
+            let mut consumed = 0;
+            for &b in buf.as_read() {
+                consumed += 1;
+                if let Some(field) = scan.step(b) {
+                    into[n] = field;
+                    n += 1;
+                    break;
+                }
+            }
+            buf.span.0 += consumed;
+        }
+        Ok(n)
+    }
+}
+
+impl CsvScan {
+    /// Advances over `b`, returning a field completed by it.
+    fn step(&mut self, b: u8) -> Option<CsvField> {
+        self.in_record = true;
+        if self.in_quotes {
+            if self.quote_pending {
+                self.quote_pending = false;
+                if b == self.quote {
+                    self.field.push(b);
+                    return None;
+                }
+                // The pending quote closed the field.
+                self.in_quotes = false;
+            } else {
+                if b == self.quote {
+                    self.quote_pending = true;
+                } else {
+                    self.field.push(b);
+                }
+                return None;
+            }
+        }
+
+        match b {
+            _ if b == self.quote && self.field.is_empty() && !self.quoted => {
+                (self.quoted, self.in_quotes) = (true, true);
+                None
+            }
+            _ if b == self.delimiter => Some(self.emit(false)),
+            b'\n' => Some(self.emit(true)),
+            b'\r' => None,
+            _ => {
+                self.field.push(b);
+                None
+            }
+        }
+    }
+
+    /// Completes the last field, at the end of the stream.
+    fn finish(&mut self) -> IO<Option<CsvField>> {
+        if self.in_quotes && !self.quote_pending {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "unterminated quoted CSV field",
+            ));
+        }
+        self.in_quotes = false;
+        self.quote_pending = false;
+        Ok(self.in_record.then(|| self.emit(true)))
+    }
+
+    fn emit(&mut self, end_of_record: bool) -> CsvField {
+        self.quoted = false;
+        self.in_record = !end_of_record;
+        CsvField {
+            data: Buffer::from_copy(std::mem::take(&mut self.field)).as_source(),
+            end_of_record,
+        }
+    }
+}
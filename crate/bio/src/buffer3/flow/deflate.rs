@@ -0,0 +1,125 @@
+use {
+    super::*,
+    flate2::{
+        Compress,
+        Compression,
+        Decompress,
+        FlushCompress,
+        FlushDecompress,
+        Status,
+    },
+};
+
+/// Input read from the source at a time.
+const CHUNK: usize = 8 * 1024;
+
+impl<S> Deflate<S> {
+    pub fn new(source: S, level: u32) -> Self {
+        Self {
+            source,
+            input: Buffer::from_copy(vec![0; CHUNK]),
+            codec: Compress::new(Compression::new(level), false),
+            source_done: false,
+            finished: false,
+        }
+    }
+}
+
+impl<S> Inflate<S> {
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            input: Buffer::from_copy(vec![0; CHUNK]),
+            codec: Decompress::new(false),
+            source_done: false,
+            finished: false,
+        }
+    }
+}
+
+impl Flow<u8, u8> for DeflateFlow {
+    type Source<S: Source<u8>> = Deflate<S>;
+
+    fn flow<S: Source<u8>>(&self, inp: S) -> Self::Source<S> {
+        Deflate::new(inp, self.level)
+    }
+}
+
+impl Flow<u8, u8> for InflateFlow {
+    type Source<S: Source<u8>> = Inflate<S>;
+
+    fn flow<S: Source<u8>>(&self, inp: S) -> Self::Source<S> {
+        Inflate::new(inp)
+    }
+}
+
+impl<S: Source<u8>> Source<u8> for Deflate<S> {
+    fn source(&mut self, into: &mut [u8]) -> IO {
+        // The codec may take in input without putting anything out yet, so keep feeding it.
+        while !self.finished && !into.is_empty() {
+            if self.input.is_empty() && !self.source_done {
+                self.input.clear();
+                self.source_done = self.input.read(&mut self.source)? == 0;
+            }
+
+            let flush = if self.source_done {
+                FlushCompress::Finish
+            } else {
+                FlushCompress::None
+            };
+            let (total_in, total_out) = (self.codec.total_in(), self.codec.total_out());
+            let status = self
+                .codec
+                .compress(self.input.as_read(), into, flush)
+                .map_err(io::Error::other)?;
+            self.input.span.0 += (self.codec.total_in() - total_in) as usize;
+            self.finished = status == Status::StreamEnd;
+
+            let n = (self.codec.total_out() - total_out) as usize;
+            if n > 0 {
+                return Ok(n);
+            }
+        }
+        Ok(0)
+    }
+}
+
+impl<S: Source<u8>> Source<u8> for Inflate<S> {
+    fn source(&mut self, into: &mut [u8]) -> IO {
+        let mut stalled = false;
+        while !self.finished && !into.is_empty() {
+            // A stalled codec needs more input than is left over to make progress.
+            if (self.input.is_empty() || stalled) && !self.source_done {
+                // Grow the buffer if the stalled input fills it, so that there is room
+                // to read into and `Ok(0)` is indeed the end of the source.
+                self.input.compact();
+                if self.input.is_full() {
+                    self.input.free_mut(CHUNK);
+                }
+                self.source_done = self.input.read(&mut self.source)? == 0;
+            }
+
+            let (total_in, total_out) = (self.codec.total_in(), self.codec.total_out());
+            let status = self
+                .codec
+                .decompress(self.input.as_read(), into, FlushDecompress::None)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            let consumed = (self.codec.total_in() - total_in) as usize;
+            self.input.span.0 += consumed;
+            self.finished = status == Status::StreamEnd;
+
+            let n = (self.codec.total_out() - total_out) as usize;
+            if n > 0 {
+                return Ok(n);
+            }
+            stalled = consumed == 0;
+            if stalled && self.source_done && !self.finished {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "source ended within a deflate stream",
+                ));
+            }
+        }
+        Ok(0)
+    }
+}
@@ -0,0 +1,52 @@
+use super::*;
+
+impl<const N: usize> Deinterleave<N> {
+    /// Splits `source` into its `N` channels.
+    pub fn split<S, T>(source: S) -> [DeinterleaveChannel<N, S, T>; N] {
+        let shared = Rc::new(RefCell::new(Deinterleaved {
+            source,
+            queues: std::array::from_fn(|_| VecDeque::new()),
+            next: 0,
+            done: false,
+        }));
+        std::array::from_fn(|channel| DeinterleaveChannel {
+            shared: Rc::clone(&shared),
+            channel,
+        })
+    }
+}
+
+impl<const N: usize, S, T> Source<T> for DeinterleaveChannel<N, S, T>
+where
+    S: Source<T>,
+    T: Clone,
+{
+    fn source(&mut self, into: &mut [T]) -> IO {
+        let Deinterleaved {
+            source,
+            queues,
+            next,
+            done,
+        } = &mut *self.shared.borrow_mut();
+
+        while queues[self.channel].is_empty() && !*done && !into.is_empty() {
+            // Use the destination as scratch space for the shared source,
+            // and distribute what was read to the channel queues.
+            let read = source.source(into)?;
+            if read == 0 {
+                *done = true;
+            }
+            for item in &into[..read] {
+                queues[*next].push_back(item.clone());
+                *next = (*next + 1) % N;
+            }
+        }
+
+        let queue = &mut queues[self.channel];
+        let n = std::cmp::min(into.len(), queue.len());
+        for (cell, item) in into.iter_mut().zip(queue.drain(..n)) {
+            *cell = item;
+        }
+        Ok(n)
+    }
+}
@@ -0,0 +1,51 @@
+use super::*;
+
+impl<S> Delta<S> {
+    pub fn new(source: S) -> Self {
+        Self { source, prev: 0 }
+    }
+}
+
+impl<S> UnDelta<S> {
+    pub fn new(source: S) -> Self {
+        Self { source, acc: 0 }
+    }
+}
+
+impl Flow<i64, i64> for DeltaFlow {
+    type Source<S: Source<i64>> = Delta<S>;
+
+    fn flow<S: Source<i64>>(&self, inp: S) -> Self::Source<S> {
+        Delta::new(inp)
+    }
+}
+
+impl Flow<i64, i64> for UnDeltaFlow {
+    type Source<S: Source<i64>> = UnDelta<S>;
+
+    fn flow<S: Source<i64>>(&self, inp: S) -> Self::Source<S> {
+        UnDelta::new(inp)
+    }
+}
+
+impl<S: Source<i64>> Source<i64> for Delta<S> {
+    fn source(&mut self, into: &mut [i64]) -> IO {
+        let read = self.source.source(into)?;
+        for cell in &mut into[..read] {
+            // Starting from 0, the first value passes through.
+            (*cell, self.prev) = (cell.wrapping_sub(self.prev), *cell);
+        }
+        Ok(read)
+    }
+}
+
+impl<S: Source<i64>> Source<i64> for UnDelta<S> {
+    fn source(&mut self, into: &mut [i64]) -> IO {
+        let read = self.source.source(into)?;
+        for cell in &mut into[..read] {
+            self.acc = self.acc.wrapping_add(*cell);
+            *cell = self.acc;
+        }
+        Ok(read)
+    }
+}
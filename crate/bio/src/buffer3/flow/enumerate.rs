@@ -0,0 +1,37 @@
+use super::*;
+
+impl<S, T> Enumerate<S, T> {
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            next: 0,
+            scratch: Vec::new(),
+        }
+    }
+}
+
+impl<T: Clone + Default> Flow<T, (usize, T)> for EnumerateFlow {
+    type Source<S: Source<T>> = Enumerate<S, T>;
+
+    fn flow<S: Source<T>>(&self, inp: S) -> Self::Source<S> {
+        Enumerate::new(inp)
+    }
+}
+
+impl<S: Source<T>, T: Clone + Default> Source<(usize, T)> for Enumerate<S, T> {
+    fn source(&mut self, into: &mut [(usize, T)]) -> IO {
+        let Self {
+            source,
+            next,
+            scratch,
+        } = self;
+
+        scratch.resize(into.len(), T::default());
+        let n = source.source(scratch)?;
+        for (cell, item) in into.iter_mut().zip(scratch.drain(..n)) {
+            *cell = (*next, item);
+            *next += 1;
+        }
+        Ok(n)
+    }
+}
@@ -0,0 +1,71 @@
+use super::*;
+
+impl<S, P, T: Copy + Default> Filter<S, P, T> {
+    pub fn new(source: S, pred: P, capacity: usize) -> Self {
+        assert!(capacity > 0, "filter capacity must not be 0");
+        Self {
+            source,
+            pred,
+            buf: Buffer::from_copy(vec![T::default(); capacity]),
+            done: false,
+        }
+    }
+}
+
+impl<P, T> Flow<T, T> for FilterFlow<P>
+where
+    P: Clone + FnMut(&T) -> bool,
+    T: Copy + Default,
+{
+    type Source<S: Source<T>> = Filter<S, P, T>;
+
+    fn flow<S: Source<T>>(&self, inp: S) -> Self::Source<S> {
+        Filter::new(inp, self.pred.clone(), self.capacity)
+    }
+}
+
+impl<S, P, T> Source<T> for Filter<S, P, T>
+where
+    S: Source<T>,
+    P: FnMut(&T) -> bool,
+    T: Copy,
+{
+    fn source(&mut self, into: &mut [T]) -> IO {
+        let Self {
+            source,
+            pred,
+            buf,
+            done,
+        } = self;
+
+        let mut n = 0;
+        while n < into.len() {
+            if buf.is_empty() {
+                // Only block on the source again if nothing has been found yet.
+                if *done || n > 0 {
+                    break;
+                }
+                buf.clear();
+                if buf.read(&mut *source)? == 0 {
+                    *done = true;
+                    break;
+                }
+            }
+
+            // Items left in the buffer are picked up by the next call.
+            let mut consumed = 0;
+            for item in buf.as_read() {
+                if n == into.len() {
+                    break;
+                }
+                consumed += 1;
+                if pred(item) {
+                    into[n] = *item;
+                    n += 1;
+                }
+            }
+            buf.span.0 += consumed;
+        }
+        Ok(n)
+    }
+}
@@ -0,0 +1,56 @@
+use super::*;
+
+impl<S> Fir<S> {
+    pub fn new(source: S, taps: Vec<f64>) -> Self {
+        Self {
+            source,
+            history: VecDeque::with_capacity(taps.len()),
+            taps,
+        }
+    }
+}
+
+impl Flow<f64, f64> for FirFlow {
+    type Source<S: Source<f64>> = Fir<S>;
+
+    fn flow<S: Source<f64>>(&self, inp: S) -> Self::Source<S> {
+        Fir::new(inp, self.taps.clone())
+    }
+}
+
+impl<S: Source<f64>> Source<f64> for Fir<S> {
+    fn source(&mut self, into: &mut [f64]) -> IO {
+        let Self {
+            source,
+            taps,
+            history,
+        } = self;
+
+        let mut target = 0;
+        while target < into.len() {
+            // Samples are read in place: each input produces at most one output,
+            // so outputs never overtake the inputs still to be processed.
+            let read = source.source(&mut into[target..])?;
+            if read == 0 {
+                break;
+            }
+
+            let mut produced = 0;
+            for i in target..target + read {
+                history.push_front(into[i]);
+                history.truncate(taps.len());
+                if !taps.is_empty() && history.len() == taps.len() {
+                    into[target + produced] =
+                        taps.iter().zip(history.iter()).map(|(t, x)| t * x).sum();
+                    produced += 1;
+                }
+            }
+            target += produced;
+
+            if produced > 0 {
+                break;
+            }
+        }
+        Ok(target)
+    }
+}
@@ -0,0 +1,56 @@
+use super::*;
+
+impl<S, D, T, C, P> Flatten<S, D, T, C, P>
+where
+    Buffer<D, T, C, P>: Default,
+{
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            current: Buffer::default(),
+            done: false,
+        }
+    }
+}
+
+impl<D, T, C, P> Flow<Buffer<D, T, C, P>, T> for FlattenFlow
+where
+    Buffer<D, T, C, P>: Default,
+    C: CopyStrategy<T>,
+    D: AsRef<[T]>,
+{
+    type Source<S: Source<Buffer<D, T, C, P>>> = Flatten<S, D, T, C, P>;
+
+    fn flow<S: Source<Buffer<D, T, C, P>>>(&self, inp: S) -> Self::Source<S> {
+        Flatten::new(inp)
+    }
+}
+
+impl<S, D, T, C, P> Source<T> for Flatten<S, D, T, C, P>
+where
+    S: Source<Buffer<D, T, C, P>>,
+    C: CopyStrategy<T>,
+    D: AsRef<[T]>,
+{
+    fn source(&mut self, into: &mut [T]) -> IO {
+        let mut n = 0;
+        while n < into.len() {
+            if !self.current.is_empty() {
+                n += self.current.copy_into(&mut into[n..])?;
+                continue;
+            }
+            if self.done {
+                break;
+            }
+            // Replaces the drained buffer with the next one.
+            if self
+                .source
+                .source(std::slice::from_mut(&mut self.current))?
+                == 0
+            {
+                self.done = true;
+            }
+        }
+        Ok(n)
+    }
+}
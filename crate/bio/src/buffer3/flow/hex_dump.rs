@@ -0,0 +1,77 @@
+use {
+    super::*,
+    std::fmt::Write,
+};
+
+impl<S> HexDump<S> {
+    pub fn new(source: S, width: usize) -> Self {
+        let width = width.max(1);
+        Self {
+            source,
+            width,
+            offset: 0,
+            line: Vec::with_capacity(width),
+            text: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// Renders the buffered line into the pending text.
+    fn render_line(&mut self) {
+        let mut text = format!("{:08x}  ", self.offset);
+        for b in &self.line {
+            let _ = write!(text, "{b:02x} ");
+        }
+        for _ in self.line.len()..self.width {
+            text.push_str("   ");
+        }
+        text.push_str(" |");
+        text.extend(self.line.iter().map(|&b| match b {
+            b' '..=b'~' => b as char,
+            _ => '.',
+        }));
+        text.push_str("|\n");
+
+        self.text.extend(text.bytes());
+        self.offset += self.line.len();
+        self.line.clear();
+    }
+}
+
+impl Flow<u8, u8> for HexDumpFlow {
+    type Source<S: Source<u8>> = HexDump<S>;
+
+    fn flow<S: Source<u8>>(&self, inp: S) -> Self::Source<S> {
+        HexDump::new(inp, self.width)
+    }
+}
+
+impl<S: Source<u8>> Source<u8> for HexDump<S> {
+    fn source(&mut self, into: &mut [u8]) -> IO {
+        let mut n = 0;
+        loop {
+            while n < into.len() {
+                let Some(b) = self.text.pop_front() else {
+                    break;
+                };
+                into[n] = b;
+                n += 1;
+            }
+            if n == into.len() || self.done {
+                return Ok(n);
+            }
+
+            let filled = self.line.len();
+            self.line.resize(self.width, 0);
+            let read = self.source.source(&mut self.line[filled..])?;
+            self.line.truncate(filled + read);
+
+            if read == 0 {
+                self.done = true;
+            }
+            if self.line.len() == self.width || (self.done && !self.line.is_empty()) {
+                self.render_line();
+            }
+        }
+    }
+}
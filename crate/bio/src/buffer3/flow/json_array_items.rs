@@ -0,0 +1,122 @@
+use super::*;
+
+type Item = Buffer<Vec<u8>, u8, SCopy, SCopy>;
+
+impl<S> JsonArrayItems<S> {
+    pub fn new(source: S, chunk: usize) -> Self {
+        Self {
+            source,
+            buf: Buffer::from_copy(vec![0; chunk.max(1)]),
+            scan: JsonScan::default(),
+        }
+    }
+}
+
+impl Flow<u8, Item> for JsonArrayItemsFlow {
+    type Source<S: Source<u8>> = JsonArrayItems<S>;
+
+    fn flow<S: Source<u8>>(&self, inp: S) -> Self::Source<S> {
+        JsonArrayItems::new(inp, self.0)
+    }
+}
+
+impl<S: Source<u8>> Source<Item> for JsonArrayItems<S> {
+    fn source(&mut self, into: &mut [Item]) -> IO {
+        let Self { source, buf, scan } = self;
+
+        let mut n = 0;
+        while n < into.len() {
+            if buf.is_empty() {
+                buf.clear();
+                if buf.read(&mut *source)? == 0 {
+                    if n > 0 || scan.stage == JsonStage::Closed {
+                        break;
+                    }
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "JSON array not closed",
+                    ));
+                }
+            }
+
+            let mut consumed = 0;
+            for &b in buf.as_read() {
+                consumed += 1;
+                if let Some(item) = scan.step(b)? {
+                    into[n] = Buffer::from_copy(item).as_source();
+                    n += 1;
+                    break;
+                }
+            }
+            buf.span.0 += consumed;
+        }
+        Ok(n)
+    }
+}
+
+impl JsonScan {
+    /// Advances over `b`, returning an element completed by it.
+    fn step(&mut self, b: u8) -> IO<Option<Vec<u8>>> {
+        let malformed = |msg| Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+
+        match self.stage {
+            _ if self.stage != JsonStage::Item && b.is_ascii_whitespace() => Ok(None),
+            JsonStage::Open if b == b'[' => {
+                self.stage = JsonStage::First;
+                Ok(None)
+            }
+            JsonStage::Open => malformed("expected '[' at start of JSON array"),
+            JsonStage::First if b == b']' => {
+                self.stage = JsonStage::Closed;
+                Ok(None)
+            }
+            JsonStage::Before if b == b',' || b == b']' => malformed("missing JSON array element"),
+            JsonStage::First | JsonStage::Before => {
+                self.stage = JsonStage::Item;
+                self.step(b)
+            }
+            JsonStage::Item => self.step_item(b),
+            JsonStage::Closed => malformed("trailing data after JSON array"),
+        }
+    }
+
+    fn step_item(&mut self, b: u8) -> IO<Option<Vec<u8>>> {
+        if self.in_string {
+            if self.escaped {
+                self.escaped = false;
+            } else if b == b'\\' {
+                self.escaped = true;
+            } else if b == b'"' {
+                self.in_string = false;
+            }
+            self.item.push(b);
+            return Ok(None);
+        }
+
+        match b {
+            b',' | b']' if self.closers.is_empty() => {
+                self.stage = match b {
+                    b',' => JsonStage::Before,
+                    _ => JsonStage::Closed,
+                };
+                let mut item = std::mem::take(&mut self.item);
+                while item.last().is_some_and(u8::is_ascii_whitespace) {
+                    item.pop();
+                }
+                return Ok(Some(item));
+            }
+            b'"' => self.in_string = true,
+            b'{' => self.closers.push(b'}'),
+            b'[' => self.closers.push(b']'),
+            b'}' | b']' if self.closers.pop() != Some(b) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "mismatched bracket in JSON array element",
+                ))
+            }
+            _ => (),
+        }
+        self.item.push(b);
+        Ok(None)
+    }
+}
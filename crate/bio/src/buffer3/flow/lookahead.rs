@@ -0,0 +1,44 @@
+use super::*;
+
+impl<const N: usize, S, T: Copy + Default> Lookahead<N, S, T> {
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            window: Buffer::from_copy(vec![T::default(); N]),
+            done: false,
+        }
+    }
+}
+
+impl<const N: usize, S: Source<T>, T: Copy> Lookahead<N, S, T> {
+    /// Refills the window from the source, returning whether it got depleted.
+    fn fill(&mut self) -> IO<bool> {
+        if !self.done {
+            self.done = !self.window.ensure_available(&mut self.source, N)?;
+        }
+        Ok(self.done)
+    }
+
+    /// The next (up to) `N` items, oldest first, without consuming them.
+    ///
+    /// Fewer than `N` items are returned only near the end of the source.
+    pub fn peek_window(&mut self) -> IO<&[T]> {
+        self.fill()?;
+        Ok(self.window.as_read())
+    }
+}
+
+impl<const N: usize, T: Copy + Default> Flow<T, T> for LookaheadFlow<N> {
+    type Source<S: Source<T>> = Lookahead<N, S, T>;
+
+    fn flow<S: Source<T>>(&self, inp: S) -> Self::Source<S> {
+        Lookahead::new(inp)
+    }
+}
+
+impl<const N: usize, S: Source<T>, T: Copy> Source<T> for Lookahead<N, S, T> {
+    fn source(&mut self, into: &mut [T]) -> IO {
+        self.fill()?;
+        self.window.copy_into(into)
+    }
+}
@@ -0,0 +1,146 @@
+use super::*;
+
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 255;
+
+impl<S> Lz77<S> {
+    pub fn new(source: S, window: usize) -> Self {
+        Self {
+            source,
+            window,
+            history: VecDeque::with_capacity(window),
+            lookahead: VecDeque::with_capacity(MAX_MATCH),
+            done: false,
+        }
+    }
+
+    /// The `(offset, length)` of the longest match of the lookahead in the history.
+    fn longest_match(&self) -> (usize, usize) {
+        let Self {
+            history, lookahead, ..
+        } = self;
+        let at = |i: usize| match i.checked_sub(history.len()) {
+            None => history[i],
+            Some(i) => lookahead[i],
+        };
+
+        let pos = history.len();
+        let mut best = (0, 0);
+        for offset in 1..=pos {
+            let length = (0..lookahead.len())
+                .take_while(|&l| at(pos - offset + l) == lookahead[l])
+                .count();
+            if length > best.1 {
+                best = (offset, length);
+            }
+        }
+        best
+    }
+}
+
+impl<S> UnLz77<S> {
+    pub fn new(source: S, window: usize) -> Self {
+        Self {
+            source,
+            window,
+            history: VecDeque::with_capacity(window),
+            pending: None,
+        }
+    }
+}
+
+impl Flow<u8, Token> for Lz77Flow {
+    type Source<S: Source<u8>> = Lz77<S>;
+
+    fn flow<S: Source<u8>>(&self, inp: S) -> Self::Source<S> {
+        Lz77::new(inp, self.window)
+    }
+}
+
+impl Flow<Token, u8> for UnLz77Flow {
+    type Source<S: Source<Token>> = UnLz77<S>;
+
+    fn flow<S: Source<Token>>(&self, inp: S) -> Self::Source<S> {
+        UnLz77::new(inp, self.window)
+    }
+}
+
+impl<S: Source<u8>> Source<Token> for Lz77<S> {
+    fn source(&mut self, into: &mut [Token]) -> IO {
+        let mut n = 0;
+        while n < into.len() {
+            while !self.done && self.lookahead.len() < MAX_MATCH {
+                let mut chunk = [0u8; MAX_MATCH];
+                let want = MAX_MATCH - self.lookahead.len();
+                match self.source.source(&mut chunk[..want])? {
+                    0 => self.done = true,
+                    read => self.lookahead.extend(&chunk[..read]),
+                }
+            }
+            let Some(&literal) = self.lookahead.front() else {
+                break;
+            };
+
+            let (offset, length) = self.longest_match();
+            let (token, consumed) = if length >= MIN_MATCH {
+                (Token::Match { offset, length }, length)
+            } else {
+                (Token::Literal(literal), 1)
+            };
+            into[n] = token;
+            n += 1;
+
+            self.history.extend(self.lookahead.drain(..consumed));
+            let excess = self.history.len().saturating_sub(self.window);
+            self.history.drain(..excess);
+        }
+        Ok(n)
+    }
+}
+
+impl<S: Source<Token>> Source<u8> for UnLz77<S> {
+    fn source(&mut self, into: &mut [u8]) -> IO {
+        let mut n = 0;
+        while n < into.len() {
+            let b = match self.pending {
+                Some((offset, remaining)) => {
+                    self.pending = (remaining > 1).then_some((offset, remaining - 1));
+                    self.history[self.history.len() - offset]
+                }
+                None => {
+                    let mut token = [Token::Literal(0)];
+                    if self.source.source(&mut token)? == 0 {
+                        break;
+                    }
+                    match token[0] {
+                        Token::Literal(b) => b,
+                        Token::Match { offset, length } => {
+                            if offset == 0 || offset > self.history.len() {
+                                return Err(io::Error::new(
+                                    io::ErrorKind::InvalidData,
+                                    format!("LZ77 match offset {offset} out of window"),
+                                ));
+                            }
+                            if length < MIN_MATCH {
+                                return Err(io::Error::new(
+                                    io::ErrorKind::InvalidData,
+                                    format!("LZ77 match length {length} below {MIN_MATCH}"),
+                                ));
+                            }
+                            self.pending = Some((offset, length));
+                            continue;
+                        }
+                    }
+                }
+            };
+            into[n] = b;
+            n += 1;
+
+            self.history.push_back(b);
+            if self.history.len() > self.window {
+                self.history.pop_front();
+            }
+        }
+        Ok(n)
+    }
+}
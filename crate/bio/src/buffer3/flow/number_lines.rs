@@ -0,0 +1,49 @@
+use super::*;
+
+impl<S> NumberLines<S> {
+    pub fn new(source: S, start: usize) -> Self {
+        Self {
+            source,
+            line: start,
+            at_line_start: true,
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+impl Flow<u8, u8> for NumberLinesFlow {
+    type Source<S: Source<u8>> = NumberLines<S>;
+
+    fn flow<S: Source<u8>>(&self, inp: S) -> Self::Source<S> {
+        NumberLines::new(inp, self.start)
+    }
+}
+
+impl<S: Source<u8>> Source<u8> for NumberLines<S> {
+    fn source(&mut self, into: &mut [u8]) -> IO {
+        let mut n = 0;
+        while n < into.len() {
+            if let Some(b) = self.pending.pop_front() {
+                into[n] = b;
+                n += 1;
+                continue;
+            }
+
+            let mut chunk = [0u8; 256];
+            let want = std::cmp::min(chunk.len(), into.len() - n);
+            let read = self.source.source(&mut chunk[..want])?;
+            if read == 0 {
+                break;
+            }
+            for &b in &chunk[..read] {
+                if self.at_line_start {
+                    self.pending.extend(format!("{}\t", self.line).bytes());
+                    self.line += 1;
+                }
+                self.pending.push_back(b);
+                self.at_line_start = b == b'\n';
+            }
+        }
+        Ok(n)
+    }
+}
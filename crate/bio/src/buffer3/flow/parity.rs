@@ -0,0 +1,115 @@
+use super::*;
+
+fn xor(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0, |parity, b| parity ^ b)
+}
+
+impl<const BLOCK: usize, S> Parity<BLOCK, S> {
+    pub fn new(source: S) -> Self {
+        assert!(BLOCK > 0, "parity block must not be empty");
+        Self {
+            source,
+            filled: 0,
+            parity: 0,
+            pending: None,
+            done: false,
+        }
+    }
+}
+
+impl<const BLOCK: usize, S> CheckParity<BLOCK, S> {
+    pub fn new(source: S) -> Self {
+        assert!(BLOCK > 0, "parity block must not be empty");
+        Self {
+            source,
+            block: Vec::with_capacity(BLOCK + 1),
+            verified: VecDeque::with_capacity(BLOCK),
+            done: false,
+        }
+    }
+}
+
+impl<const BLOCK: usize> Flow<u8, u8> for ParityFlow<BLOCK> {
+    type Source<S: Source<u8>> = Parity<BLOCK, S>;
+
+    fn flow<S: Source<u8>>(&self, inp: S) -> Self::Source<S> {
+        Parity::new(inp)
+    }
+}
+
+impl<const BLOCK: usize> Flow<u8, u8> for CheckParityFlow<BLOCK> {
+    type Source<S: Source<u8>> = CheckParity<BLOCK, S>;
+
+    fn flow<S: Source<u8>>(&self, inp: S) -> Self::Source<S> {
+        CheckParity::new(inp)
+    }
+}
+
+impl<const BLOCK: usize, S: Source<u8>> Source<u8> for Parity<BLOCK, S> {
+    fn source(&mut self, into: &mut [u8]) -> IO {
+        let mut n = 0;
+        while n < into.len() {
+            if let Some(parity) = self.pending.take() {
+                into[n] = parity;
+                n += 1;
+                continue;
+            }
+            if self.done {
+                break;
+            }
+
+            let want = std::cmp::min(into.len() - n, BLOCK - self.filled);
+            let read = self.source.source(&mut into[n..n + want])?;
+            if read == 0 {
+                self.done = true;
+            }
+            self.parity ^= xor(&into[n..n + read]);
+            self.filled += read;
+            n += read;
+
+            if self.filled == BLOCK || (self.done && self.filled > 0) {
+                self.pending = Some(self.parity);
+                (self.filled, self.parity) = (0, 0);
+            }
+        }
+        Ok(n)
+    }
+}
+
+impl<const BLOCK: usize, S: Source<u8>> Source<u8> for CheckParity<BLOCK, S> {
+    fn source(&mut self, into: &mut [u8]) -> IO {
+        let mut n = 0;
+        while n < into.len() {
+            if let Some(b) = self.verified.pop_front() {
+                into[n] = b;
+                n += 1;
+                continue;
+            }
+            if self.done {
+                break;
+            }
+
+            while !self.done && self.block.len() < BLOCK + 1 {
+                let filled = self.block.len();
+                self.block.resize(BLOCK + 1, 0);
+                let read = self.source.source(&mut self.block[filled..])?;
+                self.block.truncate(filled + read);
+                self.done = read == 0;
+            }
+            match self.block.split_last() {
+                None => (),
+                Some((&parity, data)) if !data.is_empty() && xor(data) == parity => {
+                    self.verified.extend(data);
+                }
+                Some(_) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "parity mismatch",
+                    ))
+                }
+            }
+            self.block.clear();
+        }
+        Ok(n)
+    }
+}
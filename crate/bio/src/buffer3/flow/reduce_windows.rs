@@ -0,0 +1,93 @@
+use super::*;
+
+impl<const W: usize, S, T, A, F> ReduceWindows<W, S, T, A, F>
+where
+    T: Default,
+{
+    pub fn new(source: S, init: A, reduce: F, emit_partial: bool) -> Self {
+        Self {
+            source,
+            scratch: std::array::from_fn(|_| T::default()),
+            init,
+            reduce,
+            emit_partial,
+            acc: None,
+            count: 0,
+            done: false,
+        }
+    }
+}
+
+impl<const W: usize, T, A, F> Flow<T, A> for ReduceWindowsFlow<W, A, F>
+where
+    T: Default,
+    A: Clone,
+    F: Fn(A, &T) -> A + Clone,
+{
+    type Source<S: Source<T>> = ReduceWindows<W, S, T, A, F>;
+
+    fn flow<S: Source<T>>(&self, inp: S) -> Self::Source<S> {
+        ReduceWindows::new(
+            inp,
+            self.init.clone(),
+            self.reduce.clone(),
+            self.emit_partial,
+        )
+    }
+}
+
+impl<const W: usize, S, T, A, F> Source<A> for ReduceWindows<W, S, T, A, F>
+where
+    S: Source<T>,
+    A: Clone,
+    F: Fn(A, &T) -> A,
+{
+    fn source(&mut self, into: &mut [A]) -> IO {
+        let Self {
+            source,
+            scratch,
+            init,
+            reduce,
+            emit_partial,
+            acc,
+            count,
+            done,
+        } = self;
+
+        let mut target = 0;
+
+        while let (false, Some(cell)) = (*done, into.get_mut(target)) {
+            // Only ever read as much as the current window still lacks,
+            // so window boundaries never fall within a single read.
+            let read = source.source(&mut scratch[*count..])?;
+
+            if read == 0 {
+                // Source is done; Ok(0) is final.
+                *done = true;
+                if let (true, Some(partial)) = (*emit_partial, acc.take()) {
+                    *cell = partial;
+                    target += 1;
+                }
+                break;
+            }
+
+            let folded = scratch[*count..*count + read]
+                .iter()
+                .fold(acc.take().unwrap_or_else(|| init.clone()), |a, t| {
+                    reduce(a, t)
+                });
+            *count += read;
+
+            if *count == W {
+                // Window complete, emit it and start afresh.
+                *cell = folded;
+                *count = 0;
+                target += 1;
+            } else {
+                *acc = Some(folded);
+            }
+        }
+
+        Ok(target)
+    }
+}
@@ -0,0 +1,54 @@
+use super::*;
+
+/// Items discarded per read of the source, at most.
+const SCRATCH: usize = 64;
+
+impl<S, T: Copy + Default> Skip<S, T> {
+    pub fn new(source: S, n: usize) -> Self {
+        Self {
+            source,
+            remaining: n,
+            scratch: Buffer::from_copy(vec![T::default(); std::cmp::min(n, SCRATCH)]),
+            done: false,
+        }
+    }
+}
+
+impl<T: Copy + Default> Flow<T, T> for SkipFlow {
+    type Source<S: Source<T>> = Skip<S, T>;
+
+    fn flow<S: Source<T>>(&self, inp: S) -> Self::Source<S> {
+        Skip::new(inp, self.0)
+    }
+}
+
+impl<S: Source<T>, T: Copy> Source<T> for Skip<S, T> {
+    fn source(&mut self, into: &mut [T]) -> IO {
+        let Self {
+            source,
+            remaining,
+            scratch,
+            done,
+        } = self;
+
+        // Discarded items are read into the scratch buffer, so the destination is left
+        // untouched; items read past the skipped ones are kept there for the caller.
+        while *remaining > 0 && !*done {
+            scratch.clear();
+            match scratch.read(&mut *source)? {
+                0 => *done = true,
+                _ => *remaining -= scratch.shift_out(*remaining),
+            }
+        }
+
+        if !scratch.is_empty() {
+            return scratch.copy_into(into);
+        }
+        if *remaining > 0 || *done {
+            return Ok(0);
+        }
+        source
+            .source(into)
+            .tap_ok(|&read| *done = read == 0 && !into.is_empty())
+    }
+}
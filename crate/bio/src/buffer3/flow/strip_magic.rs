@@ -0,0 +1,47 @@
+use super::*;
+
+impl<S> StripMagic<S> {
+    pub fn new(source: S, magic: Vec<u8>) -> Self {
+        Self {
+            source,
+            magic,
+            matched: 0,
+        }
+    }
+}
+
+impl Flow<u8, u8> for StripMagicFlow {
+    type Source<S: Source<u8>> = StripMagic<S>;
+
+    fn flow<S: Source<u8>>(&self, inp: S) -> Self::Source<S> {
+        StripMagic::new(inp, self.magic.clone())
+    }
+}
+
+impl<S: Source<u8>> Source<u8> for StripMagic<S> {
+    fn source(&mut self, into: &mut [u8]) -> IO {
+        if into.is_empty() {
+            return Ok(0);
+        }
+        // The destination doubles as scratch space for the header.
+        while self.matched < self.magic.len() {
+            let expected = &self.magic[self.matched..];
+            let n = std::cmp::min(into.len(), expected.len());
+            let read = self.source.source(&mut into[..n])?;
+            if read == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "stream ended within magic header",
+                ));
+            }
+            if into[..read] != expected[..read] {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "magic header mismatch",
+                ));
+            }
+            self.matched += read;
+        }
+        self.source.source(into)
+    }
+}
@@ -0,0 +1,30 @@
+use super::*;
+
+impl<S> Take<S> {
+    pub fn new(source: S, n: usize) -> Self {
+        Self {
+            source,
+            remaining: n,
+        }
+    }
+}
+
+impl<T> Flow<T, T> for TakeFlow {
+    type Source<S: Source<T>> = Take<S>;
+
+    fn flow<S: Source<T>>(&self, inp: S) -> Self::Source<S> {
+        Take::new(inp, self.0)
+    }
+}
+
+impl<S: Source<T>, T> Source<T> for Take<S> {
+    fn source(&mut self, into: &mut [T]) -> IO {
+        let n = std::cmp::min(into.len(), self.remaining);
+        if n == 0 {
+            return Ok(0);
+        }
+        self.source
+            .source(&mut into[..n])
+            .tap_ok(|read| self.remaining -= read)
+    }
+}
@@ -0,0 +1,46 @@
+use super::*;
+
+impl<S, K> TeeTo<S, K> {
+    pub fn new(source: S, side: K) -> Self {
+        Self { source, side }
+    }
+
+    pub fn side(&self) -> &K {
+        &self.side
+    }
+}
+
+impl<T, K> Flow<T, T> for TeeToFlow<K>
+where
+    K: Sink<T> + Clone,
+{
+    type Source<S: Source<T>> = TeeTo<S, K>;
+
+    fn flow<S: Source<T>>(&self, inp: S) -> Self::Source<S> {
+        TeeTo::new(inp, self.0.clone())
+    }
+}
+
+impl<S, K, T> Source<T> for TeeTo<S, K>
+where
+    S: Source<T>,
+    K: Sink<T>,
+{
+    fn source(&mut self, into: &mut [T]) -> IO {
+        let read = self.source.source(into)?;
+
+        let mut teed = 0;
+        while teed < read {
+            match self.side.sink(&into[teed..read])? {
+                0 => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "side sink stopped accepting items",
+                    ))
+                }
+                n => teed += n,
+            }
+        }
+        Ok(read)
+    }
+}
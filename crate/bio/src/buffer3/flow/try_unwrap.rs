@@ -0,0 +1,61 @@
+use super::*;
+
+impl<S, T, E> TryUnwrap<S, T, E> {
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            scratch: Vec::new(),
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+impl<T, E> Flow<Result<T, E>, T> for TryUnwrapFlow
+where
+    T: Default,
+    E: Into<io::Error>,
+{
+    type Source<S: Source<Result<T, E>>> = TryUnwrap<S, T, E>;
+
+    fn flow<S: Source<Result<T, E>>>(&self, inp: S) -> Self::Source<S> {
+        TryUnwrap::new(inp)
+    }
+}
+
+impl<S, T, E> Source<T> for TryUnwrap<S, T, E>
+where
+    S: Source<Result<T, E>>,
+    T: Default,
+    E: Into<io::Error>,
+{
+    fn source(&mut self, into: &mut [T]) -> IO {
+        let Self {
+            source,
+            scratch,
+            pending,
+        } = self;
+
+        if pending.is_empty() {
+            scratch.resize_with(into.len(), || Ok(T::default()));
+            let read = source.source(scratch)?;
+            pending.extend(scratch.drain(..read));
+        }
+
+        let mut target = 0;
+        while let (Some(cell), Some(item)) = (into.get_mut(target), pending.pop_front()) {
+            match item {
+                Ok(item) => {
+                    *cell = item;
+                    target += 1;
+                }
+                Err(e) if target == 0 => return Err(e.into()),
+                Err(e) => {
+                    // Deliver what precedes the error first.
+                    pending.push_front(Err(e));
+                    break;
+                }
+            }
+        }
+        Ok(target)
+    }
+}
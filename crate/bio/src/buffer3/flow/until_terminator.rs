@@ -0,0 +1,75 @@
+use super::*;
+
+type Message = Buffer<Vec<u8>, u8, SCopy, SCopy>;
+
+impl<S> UntilTerminator<S> {
+    pub fn new(source: S, term: Vec<u8>, strict: bool) -> Self {
+        assert!(!term.is_empty(), "terminator must not be empty");
+        Self {
+            source,
+            term,
+            strict,
+            buf: Buffer::from_copy(vec![0; 1024]),
+            message: Vec::new(),
+            done: false,
+        }
+    }
+}
+
+impl Flow<u8, Message> for UntilTerminatorFlow {
+    type Source<S: Source<u8>> = UntilTerminator<S>;
+
+    fn flow<S: Source<u8>>(&self, inp: S) -> Self::Source<S> {
+        UntilTerminator::new(inp, self.term.clone(), self.strict)
+    }
+}
+
+impl<S: Source<u8>> Source<Message> for UntilTerminator<S> {
+    fn source(&mut self, into: &mut [Message]) -> IO {
+        let Self {
+            source,
+            term,
+            strict,
+            buf,
+            message,
+            done,
+        } = self;
+
+        let mut n = 0;
+        while n < into.len() && !*done {
+            if buf.is_empty() {
+                buf.clear();
+                if buf.read(&mut *source)? == 0 {
+                    *done = true;
+                    if message.is_empty() {
+                        break;
+                    }
+                    if *strict {
+                        return Err(io::Error::new(
+                            io::ErrorKind::UnexpectedEof,
+                            "message missing its terminator",
+                        ));
+                    }
+                    into[n] = Buffer::from_copy(std::mem::take(message)).as_source();
+                    n += 1;
+                    break;
+                }
+            }
+
+            // A terminator split across reads is completed by checking the message tail.
+            let mut consumed = 0;
+            for &b in buf.as_read() {
+                consumed += 1;
+                message.push(b);
+                if message.ends_with(term) {
+                    message.truncate(message.len() - term.len());
+                    into[n] = Buffer::from_copy(std::mem::take(message)).as_source();
+                    n += 1;
+                    break;
+                }
+            }
+            buf.span.0 += consumed;
+        }
+        Ok(n)
+    }
+}
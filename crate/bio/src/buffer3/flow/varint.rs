@@ -0,0 +1,107 @@
+use super::*;
+
+impl<S> Varint<S> {
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            acc: 0,
+            shift: 0,
+        }
+    }
+}
+
+impl<S> VarintEncode<S> {
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+impl Flow<u8, u64> for VarintFlow {
+    type Source<S: Source<u8>> = Varint<S>;
+
+    fn flow<S: Source<u8>>(&self, inp: S) -> Self::Source<S> {
+        Varint::new(inp)
+    }
+}
+
+impl Flow<u64, u8> for VarintEncodeFlow {
+    type Source<S: Source<u64>> = VarintEncode<S>;
+
+    fn flow<S: Source<u64>>(&self, inp: S) -> Self::Source<S> {
+        VarintEncode::new(inp)
+    }
+}
+
+impl<S: Source<u8>> Source<u64> for Varint<S> {
+    fn source(&mut self, into: &mut [u64]) -> IO {
+        let mut n = 0;
+        while n < into.len() {
+            // Each value takes at least a byte, so this many bytes cannot overflow `into`.
+            let mut chunk = [0u8; 256];
+            let want = std::cmp::min(chunk.len(), into.len() - n);
+            let read = self.source.source(&mut chunk[..want])?;
+            if read == 0 {
+                if n == 0 && self.shift > 0 {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "stream ended within a varint",
+                    ));
+                }
+                break;
+            }
+
+            for &b in &chunk[..read] {
+                // The 10th byte holds the single top bit of a u64.
+                if self.shift > 63 || (self.shift == 63 && b > 1) {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "varint overflows 64 bits",
+                    ));
+                }
+                self.acc |= u64::from(b & 0x7f) << self.shift;
+                if b & 0x80 == 0 {
+                    into[n] = self.acc;
+                    n += 1;
+                    (self.acc, self.shift) = (0, 0);
+                } else {
+                    self.shift += 7;
+                }
+            }
+            if n > 0 {
+                break;
+            }
+        }
+        Ok(n)
+    }
+}
+
+impl<S: Source<u64>> Source<u8> for VarintEncode<S> {
+    fn source(&mut self, into: &mut [u8]) -> IO {
+        let mut n = 0;
+        while n < into.len() {
+            if let Some(b) = self.pending.pop_front() {
+                into[n] = b;
+                n += 1;
+                continue;
+            }
+
+            let mut values = [0u64; 32];
+            let want = ((into.len() - n) / 10).clamp(1, values.len());
+            let read = self.source.source(&mut values[..want])?;
+            if read == 0 {
+                break;
+            }
+            for mut value in values[..read].iter().copied() {
+                while value >= 0x80 {
+                    self.pending.push_back(value as u8 | 0x80);
+                    value >>= 7;
+                }
+                self.pending.push_back(value as u8);
+            }
+        }
+        Ok(n)
+    }
+}
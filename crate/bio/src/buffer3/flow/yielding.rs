@@ -0,0 +1,53 @@
+use super::*;
+
+impl<S> Yielding<S> {
+    pub fn new(source: S, every: usize) -> Self {
+        Self {
+            source,
+            every: every.max(1),
+            produced: 0,
+            done: false,
+        }
+    }
+
+    /// Whether the underlying source is done, as opposed to just having yielded.
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+}
+
+impl<T> Flow<T, T> for YieldingFlow {
+    type Source<S: Source<T>> = Yielding<S>;
+
+    fn flow<S: Source<T>>(&self, inp: S) -> Self::Source<S> {
+        Yielding::new(inp, self.0)
+    }
+}
+
+impl<S: Source<T>, T> Source<T> for Yielding<S> {
+    fn source(&mut self, into: &mut [T]) -> IO {
+        let Self {
+            source,
+            every,
+            produced,
+            done,
+        } = self;
+
+        if *done {
+            return Ok(0);
+        }
+        if *produced >= *every {
+            // Soft yield; resume on the next call.
+            *produced = 0;
+            return Ok(0);
+        }
+
+        let n = std::cmp::min(into.len(), *every - *produced);
+        let read = source.source(&mut into[..n])?;
+        if read == 0 && n > 0 {
+            *done = true;
+        }
+        *produced += read;
+        Ok(read)
+    }
+}
@@ -0,0 +1,52 @@
+use super::*;
+
+/// Seeks the read position (`position`) within the backing store.
+///
+/// Positions are relative to the start of the backing store, with [`io::SeekFrom::End`]
+/// relative to [`Buffer::len()`]. Seeking past `limit` clamps the position to `limit`, as
+/// there is nothing to read beyond it.
+impl<C, P, D> io::Seek for Buffer<D, u8, C, P>
+where
+    D: AsRef<[u8]>,
+{
+    fn seek(&mut self, pos: io::SeekFrom) -> IO<u64> {
+        let position = match pos {
+            io::SeekFrom::Start(offset) => Some(offset),
+            io::SeekFrom::Current(offset) => (self.span.0 as u64).checked_add_signed(offset),
+            io::SeekFrom::End(offset) => (self.len() as u64).checked_add_signed(offset),
+        }
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        self.span.0 = std::cmp::min(position, self.span.1 as u64) as usize;
+        Ok(self.span.0 as u64)
+    }
+}
+
+/// Drains the available bytes, for passing buffers to `std` APIs such as [`io::copy()`].
+impl<C: CopyStrategy<u8>, P, D> io::Read for Buffer<D, u8, C, P>
+where
+    D: AsRef<[u8]>,
+{
+    fn read(&mut self, buf: &mut [u8]) -> IO {
+        self.copy_into(buf)
+    }
+}
+
+/// Fills the free area, for using buffers with `std` writers and [`write!()`]. A full
+/// buffer accepts nothing (`Ok(0)`), so `write_all` fails with
+/// [`io::ErrorKind::WriteZero`].
+impl<C: CopyStrategy<u8>, P, D> io::Write for Buffer<D, u8, C, P>
+where
+    D: AsMut<[u8]>,
+{
+    fn write(&mut self, buf: &[u8]) -> IO {
+        self.copy_from(buf)
+    }
+    fn flush(&mut self) -> IO<()> {
+        Ok(())
+    }
+}
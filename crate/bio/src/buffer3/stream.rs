@@ -1,4 +1,24 @@
-use super::*;
+use {
+    super::*,
+    std::{
+        cell::RefCell,
+        collections::VecDeque,
+        fmt,
+        fs,
+        ops,
+        path::{
+            Path,
+            PathBuf,
+        },
+        process,
+        sync::{
+            mpsc,
+            Arc,
+            Mutex,
+        },
+        thread,
+    },
+};
 
 impl<C: CopyStrategy<T>, P, D, T> Source<T> for Buffer<D, T, C, P>
 where
@@ -18,18 +38,105 @@ where
     }
 }
 
-impl<'a, S: Source<T>, T> Source<T> for &'a mut S {
+impl<S: Source<T>, T> Source<T> for &mut S {
     fn source(&mut self, into: &mut [T]) -> IO {
         <S as Source<T>>::source(self, into)
     }
 }
 
-impl<'a, S: Sink<T>, T> Sink<T> for &'a mut S {
+impl<S: Sink<T>, T> Sink<T> for &mut S {
     fn sink(&mut self, from: &[T]) -> IO {
         <S as Sink<T>>::sink(self, from)
     }
+    fn flush(&mut self) -> IO<()> {
+        <S as Sink<T>>::flush(self)
+    }
+}
+
+/// Fills the destination from `next()`, for runtime-polymorphic iterators.
+impl<T> Source<T> for &mut dyn Iterator<Item = T> {
+    fn source(&mut self, into: &mut [T]) -> IO {
+        let mut n = 0;
+        for (cell, item) in into.iter_mut().zip(&mut **self) {
+            *cell = item;
+            n += 1;
+        }
+        Ok(n)
+    }
+}
+
+/// Sources its single item, then depletes.
+impl<T> Source<T> for std::iter::Once<T> {
+    fn source(&mut self, into: &mut [T]) -> IO {
+        let Some(cell) = into.first_mut() else {
+            return Ok(0);
+        };
+        match self.next() {
+            Some(item) => {
+                *cell = item;
+                Ok(1)
+            }
+            None => Ok(0),
+        }
+    }
+}
+
+/// Is depleted from the start.
+impl<T> Source<T> for std::iter::Empty<T> {
+    fn source(&mut self, _into: &mut [T]) -> IO {
+        Ok(0)
+    }
+}
+
+/// Borrows the source mutably for each read, so that it can be shared.
+///
+/// Panics if the source is already borrowed, e.g. when reading it re-enters itself.
+impl<S: Source<T>, T> Source<T> for &RefCell<S> {
+    fn source(&mut self, into: &mut [T]) -> IO {
+        self.borrow_mut().source(into)
+    }
+}
+
+/// Borrows the sink mutably for each write, so that several writers can share it.
+///
+/// Panics if the sink is already borrowed, e.g. when writing to it re-enters itself.
+impl<S: Sink<T>, T> Sink<T> for &RefCell<S> {
+    fn sink(&mut self, from: &[T]) -> IO {
+        self.borrow_mut().sink(from)
+    }
+    fn flush(&mut self) -> IO<()> {
+        self.borrow_mut().flush()
+    }
 }
 
+macro_rules! tuple_sinks {
+    ($(($S0:ident $s0:ident $(, $S:ident $s:ident)+)),+) => {$(
+        /// Fans out to all sinks, accepting as many items as the sink accepting the fewest.
+        ///
+        /// Each sink is offered the items all sinks before it accepted, so back-pressure
+        /// from any one of them holds back the whole tuple. Items past that minimum which
+        /// an earlier sink did accept are offered to it again with the next write, so a
+        /// sink sees items twice unless all sinks keep up alike.
+        ///
+        /// Sources are combined with [`SourceExt::chain()`] instead.
+        impl<T, $S0: Sink<T> $(, $S: Sink<T>)+> Sink<T> for ($S0, $($S,)+) {
+            fn sink(&mut self, from: &[T]) -> IO {
+                let ($s0, $($s,)+) = self;
+                let n = $s0.sink(from)?;
+                $(let n = $s.sink(&from[..n])?.min(n);)+
+                Ok(n)
+            }
+            fn flush(&mut self) -> IO<()> {
+                let ($s0, $($s,)+) = self;
+                $s0.flush()?;
+                $($s.flush()?;)+
+                Ok(())
+            }
+        }
+    )+};
+}
+tuple_sinks!((A a, B b), (A a, B b, C c), (A a, B b, C c, D d));
+
 pub struct Read<S: io::Read>(pub S);
 impl<S: io::Read> Source<u8> for Read<S> {
     fn source(&mut self, into: &mut [u8]) -> IO {
@@ -37,13 +144,120 @@ impl<S: io::Read> Source<u8> for Read<S> {
     }
 }
 
+/// Sources the lines of a text reader, without their line ending (`\n` or `\r\n`), as
+/// many as fit in the destination.
+///
+/// Invalid UTF-8 is an [`io::ErrorKind::InvalidData`] error.
+pub struct Lines<R>(pub io::BufReader<R>);
+impl<R: io::Read> Source<String> for Lines<R> {
+    fn source(&mut self, into: &mut [String]) -> IO {
+        let mut n = 0;
+        for line in into {
+            line.clear();
+            if io::BufRead::read_line(&mut self.0, line)? == 0 {
+                break;
+            }
+            if line.ends_with('\n') {
+                line.pop();
+                if line.ends_with('\r') {
+                    line.pop();
+                }
+            }
+            n += 1;
+        }
+        Ok(n)
+    }
+}
+
 pub struct Write<S: io::Write>(pub S);
 impl<S: io::Write> Sink<u8> for Write<S> {
     fn sink(&mut self, from: &[u8]) -> IO {
         self.0.write(from)
     }
+    fn flush(&mut self) -> IO<()> {
+        self.0.flush()
+    }
+}
+
+/// Appends to a file, optionally syncing its data to disk on [`Sink::flush()`].
+///
+/// Syncing waits for the device to persist the data, which is typically orders of
+/// magnitude slower than the write itself; flush sparingly, e.g. once per transfuse.
+pub struct AppendFile {
+    pub file: fs::File,
+    pub sync: bool,
+}
+impl AppendFile {
+    /// Opens (or creates) the file at `path` in append mode.
+    pub fn open(path: impl AsRef<Path>, sync: bool) -> IO<Self> {
+        let file = fs::OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(path)?;
+        Ok(Self { file, sync })
+    }
+}
+impl Sink<u8> for AppendFile {
+    fn sink(&mut self, from: &[u8]) -> IO {
+        io::Write::write(&mut self.file, from)
+    }
+    fn flush(&mut self) -> IO<()> {
+        io::Write::flush(&mut self.file)?;
+        if self.sync {
+            self.file.sync_data()?;
+        }
+        Ok(())
+    }
+}
+
+/// Sources the standard output of a child process.
+///
+/// Together with [`ChildStdin`] this allows transfusing data through an external
+/// program. Note the child's stdin usually has to be dropped (closed) before its stdout
+/// reaches end-of-file.
+///
+/// ```rust
+/// # use {bio::*, std::process::{Command, Stdio}};
+/// # fn main() -> IO<()> {
+/// # #[cfg(unix)] // `cat` is not around everywhere.
+/// # {
+/// let mut cat = Command::new("cat")
+///     .stdin(Stdio::piped())
+///     .stdout(Stdio::piped())
+///     .spawn()?;
+/// let mut stdin = stream::ChildStdin(cat.stdin.take().unwrap());
+/// let stdout = stream::Child(cat.stdout.take().unwrap());
+///
+/// Buffer::from_copy([0u8; 8]).transfuse(stream::Read(b"bio".as_ref()), &mut stdin)?;
+/// drop(stdin);
+///
+/// let mut out = stream::Write(Vec::new());
+/// Buffer::from_copy([0u8; 8]).transfuse(stdout, &mut out)?;
+/// cat.wait()?;
+/// assert_eq!(out.0, b"bio");
+/// # }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Child(pub process::ChildStdout);
+impl Source<u8> for Child {
+    fn source(&mut self, into: &mut [u8]) -> IO {
+        io::Read::read(&mut self.0, into)
+    }
+}
+
+/// Sinks into the standard input of a child process. See [`Child`].
+pub struct ChildStdin(pub process::ChildStdin);
+impl Sink<u8> for ChildStdin {
+    fn sink(&mut self, from: &[u8]) -> IO {
+        io::Write::write(&mut self.0, from)
+    }
+    fn flush(&mut self) -> IO<()> {
+        io::Write::flush(&mut self.0)
+    }
 }
 
+/// Sources items by calling the closure with each destination slice.
 pub struct Delegate<F>(pub F);
 impl<F, T> Source<T> for Delegate<F>
 where
@@ -53,3 +267,797 @@ where
         self.0(into)
     }
 }
+
+/// Sinks items by calling the closure with each source slice.
+///
+/// There is nothing to hand [`Sink::flush()`] to, so flushing is a no-op.
+pub struct DelegateSink<F>(pub F);
+impl<F, T> Sink<T> for DelegateSink<F>
+where
+    F: FnMut(&[T]) -> IO,
+{
+    fn sink(&mut self, from: &[T]) -> IO {
+        self.0(from)
+    }
+}
+
+/// Defers constructing a source until it is first read from.
+///
+/// An error from the factory is returned by that first read; the source then stays
+/// depleted.
+pub struct Lazy<F, S> {
+    factory: Option<F>,
+    source: Option<S>,
+}
+impl<F, S> Lazy<F, S> {
+    pub fn new(factory: F) -> Self {
+        Self {
+            factory: Some(factory),
+            source: None,
+        }
+    }
+}
+impl<F, S, T> Source<T> for Lazy<F, S>
+where
+    F: FnOnce() -> IO<S>,
+    S: Source<T>,
+{
+    fn source(&mut self, into: &mut [T]) -> IO {
+        if let Some(factory) = self.factory.take() {
+            self.source = Some(factory()?);
+        }
+        match &mut self.source {
+            Some(source) => source.source(into),
+            None => Ok(0),
+        }
+    }
+}
+
+/// Replays a recorded sequence of reads, one per [`Source::source()`] call.
+///
+/// A recorded read that does not fit the destination is carried over to the next call,
+/// before moving on to the next recording. An empty recording produces `Ok(0)`, as does
+/// the end of the script.
+pub struct Replay<T> {
+    reads: Vec<Vec<T>>,
+    idx: usize,
+    offset: usize,
+}
+impl<T> Replay<T> {
+    pub fn new(reads: Vec<Vec<T>>) -> Self {
+        Self {
+            reads,
+            idx: 0,
+            offset: 0,
+        }
+    }
+}
+impl<T: Clone> Source<T> for Replay<T> {
+    fn source(&mut self, into: &mut [T]) -> IO {
+        let Some(read) = self.reads.get(self.idx) else {
+            return Ok(0);
+        };
+        let read = &read[self.offset..];
+        let n = std::cmp::min(into.len(), read.len());
+        into[..n].clone_from_slice(&read[..n]);
+
+        if n == read.len() {
+            self.idx += 1;
+            self.offset = 0;
+        } else {
+            self.offset += n;
+        }
+        Ok(n)
+    }
+}
+
+/// Sources the items of a borrowed [`VecDeque`], front to back, without draining it.
+///
+/// Both halves of the ring (see [`VecDeque::as_slices()`]) are served transparently.
+pub struct DequeSource<'a, T> {
+    deque: &'a VecDeque<T>,
+    offset: usize,
+}
+impl<'a, T> DequeSource<'a, T> {
+    pub fn new(deque: &'a VecDeque<T>) -> Self {
+        Self { deque, offset: 0 }
+    }
+}
+impl<T: Clone> Source<T> for DequeSource<'_, T> {
+    fn source(&mut self, into: &mut [T]) -> IO {
+        let (front, back) = self.deque.as_slices();
+        let mut skip = self.offset;
+        let mut target = 0;
+        for slice in [front, back] {
+            if skip >= slice.len() {
+                skip -= slice.len();
+                continue;
+            }
+            let slice = &slice[skip..];
+            skip = 0;
+            let rest = &mut into[target..];
+            let n = std::cmp::min(rest.len(), slice.len());
+            rest[..n].clone_from_slice(&slice[..n]);
+            target += n;
+        }
+        self.offset += target;
+        Ok(target)
+    }
+}
+
+/// Drains items from the front of the deque.
+impl<T> Source<T> for VecDeque<T> {
+    fn source(&mut self, into: &mut [T]) -> IO {
+        let n = std::cmp::min(into.len(), self.len());
+        for (cell, item) in into.iter_mut().zip(self.drain(..n)) {
+            *cell = item;
+        }
+        Ok(n)
+    }
+}
+
+/// Appends items to the back of the deque.
+impl<T: Clone> Sink<T> for VecDeque<T> {
+    fn sink(&mut self, from: &[T]) -> IO {
+        self.extend(from.iter().cloned());
+        Ok(from.len())
+    }
+}
+
+/// Drains items from the front of the array.
+#[cfg(feature = "arrayvec")]
+impl<T, const N: usize> Source<T> for arrayvec::ArrayVec<T, N> {
+    fn source(&mut self, into: &mut [T]) -> IO {
+        let n = std::cmp::min(into.len(), self.len());
+        for (cell, item) in into.iter_mut().zip(self.drain(..n)) {
+            *cell = item;
+        }
+        Ok(n)
+    }
+}
+
+/// Appends items to the back of the array, as many as the remaining capacity allows.
+#[cfg(feature = "arrayvec")]
+impl<T: Clone, const N: usize> Sink<T> for arrayvec::ArrayVec<T, N> {
+    fn sink(&mut self, from: &[T]) -> IO {
+        let n = std::cmp::min(from.len(), self.remaining_capacity());
+        self.extend(from[..n].iter().cloned());
+        Ok(n)
+    }
+}
+
+/// Receives the items already queued, blocking for one only when none are.
+///
+/// A disconnected, drained channel is depleted (`Ok(0)`).
+#[cfg(feature = "crossbeam")]
+impl<T> Source<T> for crossbeam_channel::Receiver<T> {
+    fn source(&mut self, into: &mut [T]) -> IO {
+        let Some((first, rest)) = into.split_first_mut() else {
+            return Ok(0);
+        };
+        *first = match self.try_recv() {
+            Ok(item) => item,
+            Err(_) => match self.recv() {
+                Ok(item) => item,
+                Err(crossbeam_channel::RecvError) => return Ok(0),
+            },
+        };
+        let mut n = 1;
+        for (cell, item) in rest.iter_mut().zip(self.try_iter()) {
+            *cell = item;
+            n += 1;
+        }
+        Ok(n)
+    }
+}
+
+/// Sends each item, failing with [`io::ErrorKind::BrokenPipe`] once the receiving end is
+/// gone.
+#[cfg(feature = "crossbeam")]
+impl<T: Clone> Sink<T> for crossbeam_channel::Sender<T> {
+    fn sink(&mut self, from: &[T]) -> IO {
+        for item in from {
+            self.send(item.clone())
+                .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "channel disconnected"))?;
+        }
+        Ok(from.len())
+    }
+}
+
+/// Appends items to `data`, up to `cap` items in total.
+///
+/// Items beyond the cap are refused with a short count, and once `data` holds `cap`
+/// items, any further write fails with [`io::ErrorKind::OutOfMemory`], guarding against
+/// unbounded growth when draining an untrusted source into memory.
+pub struct BoundedVec<T> {
+    pub data: Vec<T>,
+    pub cap: usize,
+}
+impl<T: Clone> Sink<T> for BoundedVec<T> {
+    fn sink(&mut self, from: &[T]) -> IO {
+        let room = self.cap.saturating_sub(self.data.len());
+        if room == 0 && !from.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::OutOfMemory,
+                "bounded vec capacity exceeded",
+            ));
+        }
+        let n = std::cmp::min(room, from.len());
+        self.data.extend_from_slice(&from[..n]);
+        Ok(n)
+    }
+}
+
+/// Passes through at most `limit` items of `inner` in total.
+///
+/// Past the limit, it either fails with [`io::ErrorKind::InvalidData`], if `inner` has
+/// more items to give, or when `truncate` is set, quietly depletes (`Ok(0)`). Guards
+/// streaming parsers against oversized inputs. The items up to the limit always come
+/// through before the error does.
+pub struct LimitRead<S> {
+    inner: S,
+    limit: usize,
+    read: usize,
+    truncate: bool,
+    exceeded: bool,
+}
+impl<S> LimitRead<S> {
+    pub fn new(inner: S, limit: usize, truncate: bool) -> Self {
+        Self {
+            inner,
+            limit,
+            read: 0,
+            truncate,
+            exceeded: false,
+        }
+    }
+
+    /// The total of items passed through so far.
+    pub fn read(&self) -> usize {
+        self.read
+    }
+}
+impl<S: Source<T>, T> Source<T> for LimitRead<S> {
+    fn source(&mut self, into: &mut [T]) -> IO {
+        let remaining = self.limit - self.read;
+        if remaining > 0 {
+            let n = std::cmp::min(into.len(), remaining);
+            return self.inner.source(&mut into[..n]).tap_ok(|n| self.read += n);
+        }
+        if self.truncate || into.is_empty() {
+            return Ok(0);
+        }
+        // Probe one item past the limit, to tell whether there is one; once found, it has
+        // been consumed, so keep failing.
+        if self.exceeded || self.inner.source(&mut into[..1])? > 0 {
+            self.exceeded = true;
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("input exceeds limit of {} items", self.limit),
+            ));
+        }
+        Ok(0)
+    }
+}
+
+/// Collects bytes into a buffer of `capacity` bytes, writing to `inner` only when it
+/// runs out of room, or on [`Sink::flush()`] — the [`io::BufWriter`] of `bio`.
+///
+/// Writes at least as long as the buffer go straight to `inner` once it is drained.
+/// Flushing fails with [`io::ErrorKind::WriteZero`] if `inner` stops accepting before
+/// the buffer is drained.
+pub struct BufSink<K> {
+    buf: Buffer<Vec<u8>, u8, SCopy, SCopy>,
+    inner: K,
+}
+impl<K> BufSink<K> {
+    pub fn new(inner: K, capacity: usize) -> Self {
+        Self {
+            buf: Buffer::from_copy(vec![0; capacity]),
+            inner,
+        }
+    }
+
+    pub fn inner(&self) -> &K {
+        &self.inner
+    }
+}
+impl<K: Sink<u8>> BufSink<K> {
+    /// Writes buffered bytes to `inner` until drained, or `inner` stops accepting.
+    fn drain(&mut self) -> IO<()> {
+        while !self.buf.is_empty() && self.buf.write(&mut self.inner)? > 0 {}
+        self.buf.compact();
+        Ok(())
+    }
+}
+impl<K: Sink<u8>> Sink<u8> for BufSink<K> {
+    fn sink(&mut self, from: &[u8]) -> IO {
+        if self.buf.free() < from.len() {
+            self.drain()?;
+        }
+        if self.buf.is_empty() && from.len() >= self.buf.len() {
+            return self.inner.sink(from);
+        }
+        self.buf.copy_from(from)
+    }
+    fn flush(&mut self) -> IO<()> {
+        self.drain()?;
+        if !self.buf.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::WriteZero,
+                "inner sink did not accept the buffered bytes",
+            ));
+        }
+        self.inner.flush()
+    }
+}
+
+/// Sources the items a producer closure sends from its own thread, so that producers can
+/// be written push-style and consumed as a pull source.
+///
+/// Once the producer is done and its items drained, the source is depleted, or fails
+/// with an [`io::ErrorKind::Other`] error if the producer panicked.
+pub struct Generated<T> {
+    receiver: mpsc::Receiver<T>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+impl<T: Send + 'static> Generated<T> {
+    pub fn new<F>(produce: F) -> Self
+    where
+        F: FnOnce(mpsc::Sender<T>) + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let thread = thread::spawn(move || produce(sender));
+        Self {
+            receiver,
+            thread: Some(thread),
+        }
+    }
+}
+impl<T> Source<T> for Generated<T> {
+    fn source(&mut self, into: &mut [T]) -> IO {
+        let Some((first, rest)) = into.split_first_mut() else {
+            return Ok(0);
+        };
+        *first = match self.receiver.try_recv() {
+            Ok(item) => item,
+            Err(_) => match self.receiver.recv() {
+                Ok(item) => item,
+                Err(mpsc::RecvError) => {
+                    return match self.thread.take().map(thread::JoinHandle::join) {
+                        Some(Err(_)) => Err(io::Error::other("generator thread panicked")),
+                        _ => Ok(0),
+                    };
+                }
+            },
+        };
+        let mut n = 1;
+        for (cell, item) in rest.iter_mut().zip(self.receiver.try_iter()) {
+            *cell = item;
+            n += 1;
+        }
+        Ok(n)
+    }
+}
+
+/// Drains items from the front of a vector shared across threads.
+///
+/// Every read takes the lock, and shifts the remaining items down, so contention and
+/// large backlogs both cost. An empty vector reads `Ok(0)`, which is final to
+/// [`Buffer::transfuse()`], so consumers racing a producer have to poll. A poisoned lock is
+/// an [`io::ErrorKind::Other`] error.
+impl<T> Source<T> for Arc<Mutex<Vec<T>>> {
+    fn source(&mut self, into: &mut [T]) -> IO {
+        let mut vec = self
+            .lock()
+            .map_err(|_| io::Error::other("shared vec lock poisoned"))?;
+        let n = std::cmp::min(into.len(), vec.len());
+        for (cell, item) in into.iter_mut().zip(vec.drain(..n)) {
+            *cell = item;
+        }
+        Ok(n)
+    }
+}
+
+/// Appends items to a vector shared across threads, taking the lock for every write.
+///
+/// A poisoned lock is an [`io::ErrorKind::Other`] error.
+impl<T: Clone> Sink<T> for Arc<Mutex<Vec<T>>> {
+    fn sink(&mut self, from: &[T]) -> IO {
+        let mut vec = self
+            .lock()
+            .map_err(|_| io::Error::other("shared vec lock poisoned"))?;
+        vec.extend_from_slice(from);
+        Ok(from.len())
+    }
+}
+
+/// Renders items through their [`fmt::Display`] into `inner`, separated by `sep`.
+///
+/// Formatting errors are [`io::ErrorKind::Other`] errors.
+pub struct DisplaySink<W> {
+    inner: W,
+    sep: String,
+    started: bool,
+}
+impl<W> DisplaySink<W> {
+    pub fn new(inner: W, sep: impl Into<String>) -> Self {
+        Self {
+            inner,
+            sep: sep.into(),
+            started: false,
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+impl<W: fmt::Write, T: fmt::Display> Sink<T> for DisplaySink<W> {
+    fn sink(&mut self, from: &[T]) -> IO {
+        for item in from {
+            if self.started {
+                self.inner.write_str(&self.sep).map_err(io::Error::other)?;
+            }
+            write!(self.inner, "{item}").map_err(io::Error::other)?;
+            self.started = true;
+        }
+        Ok(from.len())
+    }
+}
+
+/// Repeats a source indefinitely, by restarting from a pristine clone of it each time it
+/// depletes.
+///
+/// Never depletes, unless the source is empty to begin with; bound it downstream.
+pub struct Cycle<S> {
+    pristine: S,
+    source: S,
+}
+impl<S: Clone> Cycle<S> {
+    pub fn new(source: S) -> Self {
+        Self {
+            pristine: source.clone(),
+            source,
+        }
+    }
+}
+impl<S: Source<T> + Clone, T> Source<T> for Cycle<S> {
+    fn source(&mut self, into: &mut [T]) -> IO {
+        match self.source.source(into)? {
+            0 if !into.is_empty() => {
+                self.source = self.pristine.clone();
+                self.source.source(into)
+            }
+            read => Ok(read),
+        }
+    }
+}
+
+/// Checks incoming items against an `expected` sequence, for golden testing pipelines.
+///
+/// A diverging or surplus item is an [`io::ErrorKind::InvalidData`] error, and
+/// [`Expect::finish()`] fails the same way if items are missing.
+pub struct Expect<T> {
+    expected: Vec<T>,
+    pos: usize,
+}
+impl<T: PartialEq + fmt::Debug> Expect<T> {
+    pub fn new(expected: Vec<T>) -> Self {
+        Self { expected, pos: 0 }
+    }
+
+    /// Checks that all expected items were received.
+    pub fn finish(&self) -> IO<()> {
+        match self.expected.get(self.pos..) {
+            Some([]) | None => Ok(()),
+            Some(missing) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "missing {} items from #{}: {missing:?}",
+                    missing.len(),
+                    self.pos
+                ),
+            )),
+        }
+    }
+}
+impl<T: PartialEq + fmt::Debug> Sink<T> for Expect<T> {
+    fn sink(&mut self, from: &[T]) -> IO {
+        for item in from {
+            let mismatch = match self.expected.get(self.pos) {
+                Some(expected) if expected == item => {
+                    self.pos += 1;
+                    continue;
+                }
+                Some(expected) => format!("expected {expected:?}, got {item:?}"),
+                None => format!("got {item:?} past the expected end"),
+            };
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("item #{}: {mismatch}", self.pos),
+            ));
+        }
+        Ok(from.len())
+    }
+}
+
+/// Sources the bytes of an async reader, by blocking on each read with `handle`.
+///
+/// This embeds async sources into synchronous pipelines. It must not be used from within
+/// an async context (a task of the runtime): blocking there panics, or deadlocks a
+/// runtime whose worker threads are all blocked.
+#[cfg(feature = "tokio")]
+pub struct BlockOn<S> {
+    pub inner: S,
+    pub handle: tokio::runtime::Handle,
+}
+#[cfg(feature = "tokio")]
+impl<S: tokio::io::AsyncRead + Unpin> Source<u8> for BlockOn<S> {
+    fn source(&mut self, into: &mut [u8]) -> IO {
+        let Self { inner, handle } = self;
+        handle.block_on(tokio::io::AsyncReadExt::read(inner, into))
+    }
+}
+
+/// Any owned container of items, read from or written to from `pos` on, which advances
+/// with each call — arrays, [`Vec`], boxed slices and the like alike.
+///
+/// Reads and writes share `pos`: writing then reading picks up after what was written.
+/// Both deplete (`Ok(0)`) at the end of the container; a sink never grows it.
+#[derive(Clone, Debug, Default)]
+pub struct Owned<D> {
+    pub data: D,
+    pub pos: usize,
+}
+impl<D> Owned<D> {
+    pub fn new(data: D) -> Self {
+        Self { data, pos: 0 }
+    }
+}
+impl<D: AsRef<[T]>, T: Copy> Source<T> for Owned<D> {
+    fn source(&mut self, into: &mut [T]) -> IO {
+        let from = &self.data.as_ref()[self.pos..];
+        let n = std::cmp::min(into.len(), from.len());
+        into[..n].copy_from_slice(&from[..n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+impl<D: AsMut<[T]>, T: Copy> Sink<T> for Owned<D> {
+    fn sink(&mut self, from: &[T]) -> IO {
+        let into = &mut self.data.as_mut()[self.pos..];
+        let n = std::cmp::min(into.len(), from.len());
+        into[..n].copy_from_slice(&from[..n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Caps successive reads of `inner` at the sizes in `schedule`, cycling through it, to
+/// reproduce an exact sequence of short and full reads in tests.
+///
+/// A scheduled `0` makes for an `Ok(0)` read, without touching `inner`. An empty
+/// schedule caps nothing.
+pub struct Scheduled<S> {
+    pub inner: S,
+    pub schedule: Vec<usize>,
+    call: usize,
+}
+impl<S> Scheduled<S> {
+    pub fn new(inner: S, schedule: Vec<usize>) -> Self {
+        Self {
+            inner,
+            schedule,
+            call: 0,
+        }
+    }
+}
+impl<S: Source<T>, T> Source<T> for Scheduled<S> {
+    fn source(&mut self, into: &mut [T]) -> IO {
+        let cap = match self.schedule.len() {
+            0 => into.len(),
+            len => self.schedule[self.call % len],
+        };
+        self.call += 1;
+        let n = std::cmp::min(cap, into.len());
+        if n == 0 {
+            return Ok(0);
+        }
+        self.inner.source(&mut into[..n])
+    }
+}
+
+/// Routes each item to `sinks[classify(item)]`.
+///
+/// Consecutive items of the same class are written together. Accepting stops at the
+/// first item its sink does not accept, so back-pressure from any one sink stalls the
+/// whole route. A class out of range of `sinks` is an [`io::ErrorKind::InvalidInput`]
+/// error.
+///
+/// `classify` sees each item once per write; only items refused by a sink, and so
+/// offered again on a later write, are classified again. Flushing flushes every sink.
+pub struct Route<F, S> {
+    pub classify: F,
+    pub sinks: Vec<S>,
+}
+impl<F, S, T> Sink<T> for Route<F, S>
+where
+    F: FnMut(&T) -> usize,
+    S: Sink<T>,
+{
+    fn sink(&mut self, from: &[T]) -> IO {
+        let Self { classify, sinks } = self;
+
+        let mut done = 0;
+        // Each item is classified once, the one ending a run along with the next run.
+        let mut next = from.first().map(&mut *classify);
+        while let Some(class) = next.take() {
+            let mut run = 1;
+            for item in &from[done + 1..] {
+                match classify(item) {
+                    same if same == class => run += 1,
+                    other => {
+                        next = Some(other);
+                        break;
+                    }
+                }
+            }
+            let sink = sinks.get_mut(class).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("no sink for class {class}"),
+                )
+            })?;
+            let n = sink.sink(&from[done..done + run])?;
+            done += n;
+            if n < run {
+                break;
+            }
+        }
+        Ok(done)
+    }
+    fn flush(&mut self) -> IO<()> {
+        self.sinks.iter_mut().try_for_each(Sink::flush)
+    }
+}
+
+/// Sources the contents of several files, one after the other, as one stream.
+///
+/// Each file is opened when the previous one is depleted, and `on_boundary` is then
+/// called with its path. Failing to open a file is returned as the read's error.
+pub struct Files {
+    paths: Vec<PathBuf>,
+    on_boundary: Box<dyn FnMut(&Path)>,
+    next: usize,
+    current: Option<fs::File>,
+}
+mod files;
+
+/// Writes to numbered files `{prefix}.0`, `{prefix}.1`, ... in `dir`, moving on to the next
+/// one whenever the current one reaches `max_bytes` — log rotation, as a sink.
+///
+/// A write crossing the boundary is split across the two files. Each file is created
+/// (or truncated) when first written to.
+pub struct RotatingFiles {
+    pub dir: PathBuf,
+    pub prefix: String,
+    pub max_bytes: usize,
+    index: usize,
+    written: usize,
+    current: Option<fs::File>,
+}
+mod rotating_files;
+
+/// Forwards to `inner`, failing with [`io::ErrorKind::InvalidData`] if an item is less
+/// than the one before it.
+pub struct AssertSorted<S, T> {
+    pub inner: S,
+    last: Option<T>,
+}
+impl<S, T> AssertSorted<S, T> {
+    pub fn new(inner: S) -> Self {
+        Self { inner, last: None }
+    }
+}
+impl<S: Sink<T>, T: Ord + Copy> Sink<T> for AssertSorted<S, T> {
+    fn sink(&mut self, from: &[T]) -> IO {
+        let mut last = self.last;
+        for &item in from {
+            if last.is_some_and(|last| item < last) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "items out of order",
+                ));
+            }
+            last = Some(item);
+        }
+        self.inner
+            .sink(from)
+            .tap_ok(|&n| self.last = from[..n].last().copied().or(self.last))
+    }
+    fn flush(&mut self) -> IO<()> {
+        self.inner.flush()
+    }
+}
+
+/// Merges two sorted sources into one sorted source.
+///
+/// One item is peeked from each source at a time; on ties, the item of the first source
+/// comes first. When either source is done, the other one is drained.
+pub struct MergeSorted<A, B, T> {
+    a: Peeked<A, T>,
+    b: Peeked<B, T>,
+}
+struct Peeked<S, T> {
+    source: S,
+    peek: Option<T>,
+    done: bool,
+}
+mod merge_sorted;
+
+/// Accepts all items, keeping running count, sum, minimum and maximum.
+///
+/// The sum starts from `T::default()` and uses plain `+`, so it is subject to the
+/// overflow behaviour of `T`.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct Stats<T> {
+    count: usize,
+    sum: T,
+    min: Option<T>,
+    max: Option<T>,
+}
+impl<T: Copy> Stats<T> {
+    pub fn count(&self) -> usize {
+        self.count
+    }
+    pub fn sum(&self) -> T {
+        self.sum
+    }
+    pub fn min(&self) -> Option<T> {
+        self.min
+    }
+    pub fn max(&self) -> Option<T> {
+        self.max
+    }
+}
+impl<T> Sink<T> for Stats<T>
+where
+    T: Copy + PartialOrd + ops::Add<Output = T>,
+{
+    fn sink(&mut self, from: &[T]) -> IO {
+        for &item in from {
+            self.sum = self.sum + item;
+            if self.min.is_none_or(|min| item < min) {
+                self.min = Some(item);
+            }
+            if self.max.is_none_or(|max| item > max) {
+                self.max = Some(item);
+            }
+        }
+        self.count += from.len();
+        Ok(from.len())
+    }
+}
+
+/// Reinterprets a `Source<A>` as a `Source<B>`, by casting the buffered bytes of
+/// `A` items into `B` items.
+///
+/// Either size must be a multiple of the other, which is checked at compile time: `B`
+/// items are either assembled from several `A` items (e.g. `u8` or `[u8; 4]` to `u32`),
+/// or split out of each `A` item (e.g. `u32` to `u8` or `[u8; 4]`). Bytes are copied out of an internal buffer, so `B` need not be
+/// aligned like `A`. No byte-order conversion happens: the result is in host endianness,
+/// so for portable wire formats prefer an explicit decoding flow.
+///
+/// A source ending in the middle of a `B` item results in [`io::ErrorKind::UnexpectedEof`].
+#[cfg(feature = "bytemuck")]
+pub struct Cast<S, A, B> {
+    source: S,
+    buf: Buffer<Vec<A>, A, SCopy, SCopy>,
+    /// Bytes of the first buffered `A` item already cast.
+    offset: usize,
+    done: bool,
+    _item_evidence: PhantomData<B>,
+}
+#[cfg(feature = "bytemuck")]
+mod cast;
@@ -0,0 +1,75 @@
+use {
+    super::*,
+    bytemuck::Pod,
+    std::mem::size_of,
+};
+
+impl<S, A: Pod, B: Pod> Cast<S, A, B> {
+    /// Fails to compile for item sizes neither of which is a multiple of the other.
+    const COMPATIBLE: () = assert!(
+        size_of::<A>() != 0
+            && size_of::<B>() != 0
+            && (size_of::<B>().is_multiple_of(size_of::<A>())
+                || size_of::<A>().is_multiple_of(size_of::<B>())),
+        "size of one item must be a multiple of the size of the other"
+    );
+
+    /// Creates a cast source buffering up to `capacity` items of `B` at a time.
+    pub fn new(source: S, capacity: usize) -> Self {
+        let () = Self::COMPATIBLE;
+        let len = (capacity.max(1) * size_of::<B>()).div_ceil(size_of::<A>());
+        Self {
+            source,
+            buf: Buffer::from_copy(vec![A::zeroed(); len]),
+            offset: 0,
+            done: false,
+            _item_evidence: PhantomData,
+        }
+    }
+}
+
+impl<S, A: Pod, B: Pod> Source<B> for Cast<S, A, B>
+where
+    S: Source<A>,
+{
+    fn source(&mut self, into: &mut [B]) -> IO {
+        let Self {
+            source,
+            buf,
+            offset,
+            done,
+            ..
+        } = self;
+
+        let mut target = 0;
+        while target < into.len() {
+            let bytes = &bytemuck::cast_slice::<A, u8>(buf.as_read())[*offset..];
+            let whole = bytes.len() / size_of::<B>();
+            if whole > 0 {
+                let n = std::cmp::min(whole, into.len() - target);
+                let len = n * size_of::<B>();
+                bytemuck::cast_slice_mut::<B, u8>(&mut into[target..target + n])
+                    .copy_from_slice(&bytes[..len]);
+                // Narrowing may stop within an `A` item, to be resumed from `offset`.
+                let consumed = *offset + len;
+                buf.span.0 += consumed / size_of::<A>();
+                *offset = consumed % size_of::<A>();
+                target += n;
+            } else if *done {
+                if !buf.is_empty() && target == 0 {
+                    buf.clear();
+                    *offset = 0;
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "source ended within an item",
+                    ));
+                }
+                break;
+            } else {
+                buf.compact();
+                *done = buf.read(&mut *source)? == 0;
+            }
+        }
+        Ok(target)
+    }
+}
@@ -0,0 +1,36 @@
+use super::*;
+
+impl Files {
+    pub fn new(paths: Vec<PathBuf>, on_boundary: impl FnMut(&Path) + 'static) -> Self {
+        Self {
+            paths,
+            on_boundary: Box::new(on_boundary),
+            next: 0,
+            current: None,
+        }
+    }
+}
+
+impl Source<u8> for Files {
+    fn source(&mut self, into: &mut [u8]) -> IO {
+        loop {
+            let file = match &mut self.current {
+                Some(file) => file,
+                None => {
+                    let Some(path) = self.paths.get(self.next) else {
+                        return Ok(0);
+                    };
+                    let file = fs::File::open(path)?;
+                    (self.on_boundary)(path);
+                    self.next += 1;
+                    self.current.insert(file)
+                }
+            };
+
+            match io::Read::read(file, into)? {
+                0 if !into.is_empty() => self.current = None,
+                n => return Ok(n),
+            }
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use super::*;
+
+impl<A, B, T> MergeSorted<A, B, T> {
+    pub fn new(a: A, b: B) -> Self {
+        Self {
+            a: Peeked::new(a),
+            b: Peeked::new(b),
+        }
+    }
+}
+
+impl<S, T> Peeked<S, T> {
+    fn new(source: S) -> Self {
+        Self {
+            source,
+            peek: None,
+            done: false,
+        }
+    }
+}
+
+impl<S: Source<T>, T: Copy> Peeked<S, T> {
+    /// Peeks the next item, using `scratch` as the single-slot destination.
+    fn peek(&mut self, scratch: &mut T) -> IO<Option<T>> {
+        if let (None, false) = (self.peek, self.done) {
+            if self.source.source(std::slice::from_mut(scratch))? == 0 {
+                self.done = true;
+            } else {
+                self.peek = Some(*scratch);
+            }
+        }
+        Ok(self.peek)
+    }
+}
+
+impl<A, B, T> Source<T> for MergeSorted<A, B, T>
+where
+    A: Source<T>,
+    B: Source<T>,
+    T: Ord + Copy,
+{
+    fn source(&mut self, into: &mut [T]) -> IO {
+        let Self { a, b } = self;
+
+        let mut target = 0;
+        while let Some(cell) = into.get_mut(target) {
+            let item = match (a.peek(cell)?, b.peek(cell)?) {
+                (Some(x), Some(y)) if y < x => b.peek.take(),
+                (Some(_), _) => a.peek.take(),
+                (None, _) => b.peek.take(),
+            };
+            if let Some(item) = item {
+                *cell = item;
+                target += 1;
+            } else {
+                break;
+            }
+        }
+        Ok(target)
+    }
+}
@@ -0,0 +1,54 @@
+use super::*;
+
+impl RotatingFiles {
+    pub fn new(dir: impl Into<PathBuf>, prefix: impl Into<String>, max_bytes: usize) -> Self {
+        assert!(max_bytes > 0, "rotated files must not be empty");
+        Self {
+            dir: dir.into(),
+            prefix: prefix.into(),
+            max_bytes,
+            index: 0,
+            written: 0,
+            current: None,
+        }
+    }
+
+    /// The path of the `index`th file.
+    pub fn path(&self, index: usize) -> PathBuf {
+        self.dir.join(format!("{}.{index}", self.prefix))
+    }
+
+    /// The number of files written to so far.
+    pub fn files(&self) -> usize {
+        self.index
+    }
+}
+
+impl Sink<u8> for RotatingFiles {
+    fn sink(&mut self, from: &[u8]) -> IO {
+        let mut done = 0;
+        while done < from.len() {
+            let file = match &mut self.current {
+                Some(file) if self.written < self.max_bytes => file,
+                _ => {
+                    let file = fs::File::create(self.path(self.index))?;
+                    self.index += 1;
+                    self.written = 0;
+                    self.current.insert(file)
+                }
+            };
+
+            let n = std::cmp::min(from.len() - done, self.max_bytes - self.written);
+            io::Write::write_all(file, &from[done..done + n])?;
+            self.written += n;
+            done += n;
+        }
+        Ok(done)
+    }
+    fn flush(&mut self) -> IO<()> {
+        match &mut self.current {
+            Some(file) => io::Write::flush(file),
+            None => Ok(()),
+        }
+    }
+}
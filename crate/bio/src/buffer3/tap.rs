@@ -2,6 +2,7 @@ use std::borrow::Borrow;
 
 impl<S> Tap for S {}
 
+#[allow(dead_code)]
 pub trait Tap: Sized {
     fn tap(self, block: impl FnOnce(&Self)) -> Self {
         block(&self);
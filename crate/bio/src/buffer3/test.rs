@@ -179,7 +179,7 @@ fn cbuffer_transfuse_short_source() -> IO<()> {
 #[test]
 fn buffer_io() -> IO<()> {
     let read = [0, 1, 2, 3, 4, 5u8].as_ref();
-    let mut source = stream::Read(read.as_ref());
+    let mut source = stream::Read(read);
 
     let mut write = [0u8; 19];
     let mut sink = stream::Write(write.as_mut());
@@ -190,3 +190,2172 @@ fn buffer_io() -> IO<()> {
     assert_eq!(write.as_mut()[0..7], [0, 1, 2, 3, 4, 5, 0]);
     Ok(())
 }
+#[test]
+fn flow_reduce_windows() -> IO<()> {
+    let source = Buffer::from_copy([1, 2, 3, 4, 5, 6u32]).as_source();
+    let flow = flow::ReduceWindowsFlow::<3, _, _> {
+        init: 0,
+        reduce: |a, t: &u32| a + t,
+        emit_partial: false,
+    };
+    let mut sums = flow.flow(source);
+
+    let mut sink = Buffer::from_copy([0u32; 4]);
+    let n = sink.read(&mut sums)?;
+
+    assert_eq!(n, 2);
+    assert_eq!(sink.as_read(), [6, 15]);
+    assert_eq!(sums.source(&mut [0u32; 1])?, 0);
+    Ok(())
+}
+#[test]
+fn flow_reduce_windows_partial() -> IO<()> {
+    let source = Buffer::from_copy([1, 2, 3, 4, 5u32]).as_source();
+    let flow = |emit_partial| flow::ReduceWindowsFlow::<3, _, _> {
+        init: 0,
+        reduce: |a, t: &u32| a + t,
+        emit_partial,
+    };
+
+    let mut sink = Buffer::from_copy([0u32; 4]);
+    sink.read(flow(true).flow(source))?;
+    assert_eq!(sink.as_read(), [6, 9]);
+
+    let mut sink = Buffer::from_copy([0u32; 4]);
+    sink.read(flow(false).flow(source))?;
+    assert_eq!(sink.as_read(), [6]);
+    Ok(())
+}
+#[cfg(all(feature = "bytemuck", target_endian = "little"))]
+#[test]
+fn stream_cast() -> IO<()> {
+    let bytes = stream::Read([1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0u8].as_ref());
+    let mut ints = stream::Cast::<_, u8, u32>::new(bytes, 2);
+
+    let mut dest = [0u32; 2];
+    assert_eq!(ints.source(&mut dest)?, 2);
+    assert_eq!(dest, [1, 2]);
+    assert_eq!(ints.source(&mut dest)?, 1);
+    assert_eq!(dest[0], 3);
+    assert_eq!(ints.source(&mut dest)?, 0);
+
+    // Narrowing splits each item, resuming within one across reads.
+    let ints = Buffer::from_copy([0x0403_0201u32, 0x0807_0605]).as_source();
+    let mut bytes = stream::Cast::<_, u32, u8>::new(ints, 3);
+    let mut dest = [0u8; 3];
+    assert_eq!(bytes.source(&mut dest)?, 3);
+    assert_eq!(dest, [1, 2, 3]);
+    assert_eq!(bytes.source(&mut dest)?, 3);
+    assert_eq!(dest, [4, 5, 6]);
+    assert_eq!(bytes.source(&mut dest)?, 2);
+    assert_eq!(dest[..2], [7, 8]);
+    assert_eq!(bytes.source(&mut dest)?, 0);
+
+    let ints = Buffer::from_copy([0x0403_0201u32]).as_source();
+    let mut arrays = stream::Cast::<_, u32, [u8; 2]>::new(ints, 1);
+    let mut dest = [[0u8; 2]; 4];
+    assert_eq!(arrays.source(&mut dest)?, 2);
+    assert_eq!(dest[..2], [[1, 2], [3, 4]]);
+    Ok(())
+}
+#[cfg(feature = "bytemuck")]
+#[test]
+fn stream_cast_partial_item() -> IO<()> {
+    let bytes = stream::Read([1, 0, 0, 0, 2u8].as_ref());
+    let mut ints = stream::Cast::<_, u8, u32>::new(bytes, 4);
+
+    let mut dest = [0u32; 4];
+    assert_eq!(ints.source(&mut dest)?, 1);
+    let err = ints.source(&mut dest).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    Ok(())
+}
+#[test]
+fn dyn_buffer_transfuse() -> IO<()> {
+    let mut source = DynBuffer::from_copy([1, 2, 3, 4, 5u8]).as_source();
+    let mut sink = DynBuffer::from_clone([0u8; 4]);
+
+    let mut buf = DynBuffer::from_copy([0u8; 3]);
+
+    let n = buf.transfuse(&mut source, &mut sink)?;
+
+    assert_eq!(n, 4);
+    assert_eq!(source.as_read(), []);
+    assert_eq!(sink.as_read(), [1, 2, 3, 4]);
+    assert_eq!(buf.as_read(), [5]);
+    Ok(())
+}
+#[test]
+fn dyn_buffer_switch_compactor() -> IO<()> {
+    fn compact_none(_: &mut [u8], _: Range<usize>) {}
+    let use_copy = |copy: bool| -> fn(&mut [u8], Range<usize>) {
+        if copy {
+            <SCopy as CompactStrategy<u8>>::compact_within
+        } else {
+            <SNone as CompactStrategy<u8>>::compact_within
+        }
+    };
+
+    let mut buf = DynBuffer::from_copy([0u8; 5]).with_compactor(use_copy(false));
+    buf.copy_from(&[1, 2, 3, 4, 5])?;
+    buf.copy_into(&mut [0u8; 2])?;
+    buf.compact();
+    assert_eq!(buf.as_read(), [3, 4, 5]);
+
+    buf.set_compactor(use_copy(true));
+    buf.copy_into(&mut [0u8; 1])?;
+    buf.compact();
+    assert_eq!(buf.as_read(), [4, 5]);
+
+    // A compactor that does not move anything breaks the span invariant,
+    // showing the chosen function pointer is the one in use.
+    buf.set_compactor(compact_none);
+    buf.copy_into(&mut [0u8; 1])?;
+    buf.compact();
+    assert_eq!(buf.as_read(), [4]);
+    Ok(())
+}
+#[test]
+fn stream_assert_sorted() -> IO<()> {
+    let mut sink = stream::AssertSorted::new(Buffer::from_copy([0u8; 8]));
+    let n = Buffer::from_copy([0u8; 2])
+        .transfuse(Buffer::from_copy([1, 2, 2, 5, 8u8]).as_source(), &mut sink)?;
+    assert_eq!(n, 5);
+    assert_eq!(sink.inner.as_read(), [1, 2, 2, 5, 8]);
+
+    let mut sink = stream::AssertSorted::new(Buffer::from_copy([0u8; 8]));
+    let err = Buffer::from_copy([0u8; 2])
+        .transfuse(Buffer::from_copy([1, 3, 2u8]).as_source(), &mut sink)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(sink.inner.as_read(), [1, 3]);
+
+    let mut sink = stream::AssertSorted::new(stream::BufSink::new(CountingSink::default(), 8));
+    assert_eq!(sink.sink(&[1, 2, 3])?, 3);
+    assert_eq!(sink.inner.inner().items, 0);
+    sink.flush()?;
+    assert_eq!(sink.inner.inner().items, 3);
+    Ok(())
+}
+#[test]
+fn buffer_transfuse_n_cycles() -> IO<()> {
+    let mut source = Buffer::from_copy([1, 2, 3, 4, 5u8]).as_source();
+    let mut sink = Buffer::from_copy([0u8; 2]);
+    let mut buf = Buffer::from_copy([0u8; 3]);
+
+    let n = buf.transfuse_n_cycles(&mut source, &mut sink, 1)?;
+    assert_eq!(n, 2);
+    assert_eq!(source.as_read(), [4, 5]);
+    assert_eq!(buf.as_read(), [3]);
+    assert_eq!(sink.as_read(), [1, 2]);
+
+    let n = buf.transfuse_n_cycles(&mut source, &mut sink, 1)?;
+    assert_eq!(n, 0);
+    assert_eq!(source.as_read(), []);
+    assert_eq!(buf.as_read(), [3, 4, 5]);
+
+    let n = buf.transfuse_n_cycles(&mut source, &mut sink, 0)?;
+    assert_eq!(n, 0);
+    assert_eq!(buf.as_read(), [3, 4, 5]);
+    Ok(())
+}
+#[cfg(unix)]
+#[test]
+fn stream_child() -> IO<()> {
+    use std::process::{
+        Command,
+        Stdio,
+    };
+
+    let input: Vec<u8> = (0..=255u8).cycle().take(4096).collect();
+
+    let mut cat = Command::new("cat")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    let mut stdin = stream::ChildStdin(cat.stdin.take().unwrap());
+    let mut stdout = stream::Child(cat.stdout.take().unwrap());
+
+    let fed = std::thread::spawn(move || {
+        Buffer::from_copy([0u8; 64]).transfuse(stream::Read(input.as_slice()), &mut stdin)
+    });
+
+    let mut output = stream::Write(Vec::new());
+    let n = Buffer::from_copy([0u8; 64]).transfuse(&mut stdout, &mut output)?;
+
+    assert_eq!(fed.join().unwrap()?, 4096);
+    assert_eq!(n, 4096);
+    assert!(cat.wait()?.success());
+    assert!(output.0.iter().copied().eq((0..=255u8).cycle().take(4096)));
+    Ok(())
+}
+#[test]
+fn buffer_as_str() -> IO<()> {
+    let buf = Buffer::from_copy(*b"bio \xce\xbb").as_source();
+    assert_eq!(buf.as_str()?, "bio λ");
+
+    let mut buf = Buffer::from_copy([0u8; 8]);
+    buf.copy_from(b"bio \xce")?;
+    let err = buf.as_str().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    assert_eq!(buf.consume_str()?, "bio ");
+    assert_eq!(buf.as_read(), b"\xce");
+    buf.copy_from(b"\xbb")?;
+    assert_eq!(buf.consume_str()?, "λ");
+    assert!(buf.is_empty());
+
+    buf.copy_from(b"\xff")?;
+    let err = buf.consume_str().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    Ok(())
+}
+#[test]
+fn flow_yielding() -> IO<()> {
+    let source = Buffer::from_copy([1, 2, 3, 4, 5u8]).as_source();
+    let mut source = flow::YieldingFlow(2).flow(source);
+
+    let mut sink = Buffer::from_copy([0u8; 8]);
+    assert_eq!(sink.read(&mut source)?, 2);
+    assert_eq!(sink.read(&mut source)?, 0);
+    assert!(!source.is_done());
+
+    assert_eq!(sink.read(&mut source)?, 2);
+    assert_eq!(sink.read(&mut source)?, 0);
+    assert!(!source.is_done());
+
+    assert_eq!(sink.read(&mut source)?, 1);
+    assert_eq!(sink.read(&mut source)?, 0);
+    assert!(source.is_done());
+
+    assert_eq!(sink.as_read(), [1, 2, 3, 4, 5]);
+    Ok(())
+}
+#[test]
+fn buffer_with_span() -> IO<()> {
+    let mut buf = Buffer::from_copy([0u8; 6]);
+    buf.copy_from(&[1, 2, 3, 4])?;
+    buf.copy_into(&mut [0u8; 1])?;
+
+    let (data, (start, end)) = (buf.data, buf.span());
+    let restored = Buffer::from_copy_with_span(data, start, end)?;
+    assert_eq!(restored.as_read(), buf.as_read());
+    assert_eq!(restored.as_read(), [2, 3, 4]);
+    assert_eq!(restored.free(), buf.free());
+
+    for (start, end) in [(2, 1), (0, 7)] {
+        let err = Buffer::from_copy_with_span(data, start, end).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+    Ok(())
+}
+#[test]
+fn stream_merge_sorted() -> IO<()> {
+    let a = Buffer::from_copy([1, 3, 5u8]).as_source();
+    let b = Buffer::from_copy([2, 4, 6, 7u8]).as_source();
+    let mut merged = stream::MergeSorted::new(a, b);
+
+    let mut sink = Buffer::from_copy([0u8; 8]);
+    let n = Buffer::from_copy([0u8; 2]).transfuse(&mut merged, &mut sink)?;
+
+    assert_eq!(n, 7);
+    assert_eq!(sink.as_read(), [1, 2, 3, 4, 5, 6, 7]);
+    Ok(())
+}
+#[derive(Default)]
+struct CountingSink {
+    calls: usize,
+    items: usize,
+}
+impl<T> Sink<T> for CountingSink {
+    fn sink(&mut self, from: &[T]) -> IO {
+        self.calls += 1;
+        self.items += from.len();
+        Ok(from.len())
+    }
+}
+#[test]
+fn buffer_transfuse_empty_source() -> IO<()> {
+    let mut sink = CountingSink::default();
+    let mut buf = Buffer::from_copy([0u8; 3]);
+
+    let n = buf.transfuse(Buffer::from_copy([0u8; 0]).as_source(), &mut sink)?;
+
+    assert_eq!(n, 0);
+    assert_eq!(sink.calls, 0);
+
+    let n = buf.transfuse(Buffer::from_copy([1, 2, 3, 4u8]).as_source(), &mut sink)?;
+
+    assert_eq!(n, 4);
+    assert_eq!(sink.calls, 2);
+    assert_eq!(sink.items, 4);
+    Ok(())
+}
+#[test]
+fn stream_append_file() -> IO<()> {
+    let path = std::env::temp_dir().join(format!("bio-append-{}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let mut buf = Buffer::from_copy([0u8; 4]);
+    for (chunk, sync) in [(b"hello, ".as_ref(), false), (b"bio!", true)] {
+        let mut sink = stream::AppendFile::open(&path, sync)?;
+        buf.transfuse(stream::Read(chunk), &mut sink)?;
+        sink.flush()?;
+    }
+
+    let content = std::fs::read(&path)?;
+    std::fs::remove_file(&path)?;
+    assert_eq!(content, b"hello, bio!");
+    Ok(())
+}
+#[test]
+fn buffer_iter_chunks() -> IO<()> {
+    let mut buf = Buffer::from_copy([0u8; 12]);
+    buf.copy_from(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9])?;
+    buf.copy_into(&mut [0u8; 1])?;
+
+    let pixels: Vec<&[u8; 4]> = buf.iter_chunks::<4>().collect();
+    assert_eq!(pixels, [&[1, 2, 3, 4], &[5, 6, 7, 8]]);
+    Ok(())
+}
+#[test]
+fn flow_deinterleave() -> IO<()> {
+    let frames = Buffer::from_copy([10, 20, 11, 21, 12, 22u8]).as_source();
+    let [mut left, mut right] = flow::Deinterleave::<2>::split(frames);
+
+    let mut l = Buffer::from_copy([0u8; 4]);
+    let mut r = Buffer::from_copy([0u8; 4]);
+
+    l.read(&mut left)?;
+    assert_eq!(l.as_read(), [10, 11]);
+    r.read(&mut right)?;
+    assert_eq!(r.as_read(), [20, 21]);
+
+    r.read(&mut right)?;
+    l.read(&mut left)?;
+    assert_eq!(l.as_read(), [10, 11, 12]);
+    assert_eq!(r.as_read(), [20, 21, 22]);
+
+    assert_eq!(l.read(&mut left)?, 0);
+    assert_eq!(r.read(&mut right)?, 0);
+    Ok(())
+}
+#[test]
+fn buffer_write_to_first() -> IO<()> {
+    let mut buf = Buffer::from_copy([1, 2, 3u8]).as_source();
+    let mut sinks = [Buffer::from_copy([9u8; 2]), Buffer::from_copy([0u8; 2])];
+    sinks[0].copy_from(&[9, 9])?;
+
+    assert_eq!(buf.write_to_first(&mut sinks)?, Some((1, 2)));
+    assert_eq!(sinks[1].as_read(), [1, 2]);
+    assert_eq!(buf.as_read(), [3]);
+
+    assert_eq!(buf.write_to_first(&mut sinks)?, None);
+    assert_eq!(buf.as_read(), [3]);
+    Ok(())
+}
+#[test]
+fn stream_lazy() -> IO<()> {
+    let opened = std::cell::Cell::new(false);
+    let mut source = stream::Lazy::new(|| {
+        opened.set(true);
+        Ok(Buffer::from_copy([1, 2, 3u8]).as_source())
+    });
+    assert!(!opened.get());
+
+    let mut sink = Buffer::from_copy([0u8; 4]);
+    sink.read(&mut source)?;
+    assert!(opened.get());
+    assert_eq!(sink.as_read(), [1, 2, 3]);
+
+    let mut failing =
+        stream::Lazy::new(|| -> IO<stream::Read<&[u8]>> { Err(io::ErrorKind::NotFound.into()) });
+    let err = failing.source(&mut [0u8; 1]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert_eq!(failing.source(&mut [0u8; 1])?, 0);
+    Ok(())
+}
+#[test]
+fn buffer_transfuse_with_header() -> IO<()> {
+    // A sink accepting a single byte per write
+    struct Trickle<'a>(&'a mut Vec<u8>);
+    impl Sink<u8> for Trickle<'_> {
+        fn sink(&mut self, from: &[u8]) -> IO {
+            self.0.extend(from.iter().take(1));
+            Ok(from.len().min(1))
+        }
+    }
+
+    let mut out = Vec::new();
+    let n = Buffer::from_copy([0u8; 2]).transfuse_with_header(
+        b"BIO\0",
+        stream::Read(b"body".as_ref()),
+        Trickle(&mut out),
+    )?;
+    assert_eq!(n, 8);
+    assert_eq!(out, b"BIO\0body");
+
+    let mut sink = Buffer::from_copy([0u8; 2]);
+    let err = Buffer::from_copy([0u8; 2])
+        .transfuse_with_header(b"BIO\0", stream::Read(b"body".as_ref()), &mut sink)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+    Ok(())
+}
+#[test]
+fn stream_stats() -> IO<()> {
+    let mut stats = stream::Stats::<u32>::default();
+    assert_eq!((stats.min(), stats.max()), (None, None));
+
+    Buffer::from_copy([0u32; 2]).transfuse(
+        Buffer::from_copy([3, 1, 4, 1, 5u32]).as_source(),
+        &mut stats,
+    )?;
+    assert_eq!(stats.count(), 5);
+    assert_eq!(stats.sum(), 14);
+    assert_eq!(stats.min(), Some(1));
+    assert_eq!(stats.max(), Some(5));
+    Ok(())
+}
+#[test]
+fn flow_skip() -> IO<()> {
+    let mut sink = Buffer::from_copy([0u8; 8]);
+    let n = Buffer::from_copy([0u8; 2]).transfuse(
+        Buffer::from_copy([1, 2, 3, 4, 5u8]).as_source().skip(2),
+        &mut sink,
+    )?;
+    assert_eq!(n, 3);
+    assert_eq!(sink.as_read(), [3, 4, 5]);
+
+    // Skipped items never reach the destination.
+    let mut short = Buffer::from_copy([1, 2u8]).as_source().skip(3);
+    let mut dest = [0u8; 4];
+    assert_eq!(short.source(&mut dest)?, 0);
+    assert_eq!(dest, [0; 4]);
+    assert_eq!(short.source(&mut dest)?, 0);
+
+    // Skipping makes progress even with nowhere to put the rest.
+    let mut source = Buffer::from_copy([1, 2, 3u8]).as_source();
+    let mut skipped = (&mut source).skip(2);
+    assert_eq!(skipped.source(&mut [])?, 0);
+    drop(skipped);
+    assert_eq!(source.as_read(), [3]);
+
+    // Items read past the skipped ones are kept for the next read.
+    let mut skipped = Throttle {
+        inner: Buffer::from_copy([1, 2, 3, 4, 5u8]).as_source(),
+        max: 2,
+    }
+    .skip(3);
+    let mut dest = [0u8; 4];
+    assert_eq!(skipped.source(&mut dest)?, 1);
+    assert_eq!(dest[0], 4);
+    assert_eq!(skipped.source(&mut dest)?, 1);
+    assert_eq!(dest[0], 5);
+    assert_eq!(skipped.source(&mut dest)?, 0);
+    Ok(())
+}
+#[test]
+fn flow_skip_narrow_destination() -> IO<()> {
+    let skip = flow::SkipFlow(2);
+    let mut source = skip.flow(Throttle {
+        inner: Buffer::from_copy([10, 20, 30, 40]).as_source(),
+        max: 1,
+    });
+
+    // The discarded items are read through the scratch buffer, one at a time.
+    let mut dest = [0; 1];
+    assert_eq!(source.source(&mut dest)?, 1);
+    assert_eq!(dest, [30]);
+    assert_eq!(source.source(&mut dest)?, 1);
+    assert_eq!(dest, [40]);
+    assert_eq!(source.source(&mut dest)?, 0);
+    Ok(())
+}
+#[test]
+fn buffer_io_read() -> IO<()> {
+    let mut buf = Buffer::from_copy_with_span(*b"xhello, bio", 1, 6)?;
+    let mut out = Vec::new();
+    assert_eq!(io::copy(&mut buf, &mut out)?, 5);
+    assert_eq!(out, b"hello");
+    assert!(buf.is_empty());
+    assert_eq!(buf.span(), (6, 6));
+    Ok(())
+}
+#[test]
+fn buffer_io_write() -> IO<()> {
+    use std::io::Write;
+
+    let mut buf = Buffer::from_copy([0u8; 8]);
+    buf.write_all(b"hello")?;
+    assert_eq!(buf.as_read(), b"hello");
+    write!(buf, "{}", 42)?;
+    assert_eq!(buf.as_read(), b"hello42");
+
+    let err = buf.write_all(b"!!").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+    assert_eq!(buf.as_read(), b"hello42!");
+    Ok(())
+}
+#[test]
+fn buffer_seek() -> IO<()> {
+    use std::io::{
+        Seek,
+        SeekFrom,
+    };
+
+    let mut buf = Buffer::from_copy([1, 2, 3, 4, 5, 6u8]).as_source();
+
+    assert_eq!(buf.seek(SeekFrom::Start(2))?, 2);
+    let mut dest = [0u8; 2];
+    buf.copy_into(&mut dest)?;
+    assert_eq!(dest, [3, 4]);
+
+    assert_eq!(buf.seek(SeekFrom::Current(-3))?, 1);
+    assert_eq!(buf.as_read(), [2, 3, 4, 5, 6]);
+
+    assert_eq!(buf.seek(SeekFrom::End(-1))?, 5);
+    assert_eq!(buf.as_read(), [6]);
+
+    assert_eq!(buf.seek(SeekFrom::Start(10))?, 6);
+    assert!(buf.is_empty());
+
+    let err = buf.seek(SeekFrom::Current(-7)).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    Ok(())
+}
+#[test]
+fn flow_fir() -> IO<()> {
+    let samples = Buffer::from_copy([2.0, 4.0, 6.0, 8.0, 4.0f64]).as_source();
+    let averaged = flow::FirFlow {
+        taps: vec![0.5, 0.5],
+    }
+    .flow(samples);
+
+    let mut sink = Buffer::from_copy([0f64; 8]);
+    let n = Buffer::from_copy([0f64; 2]).transfuse(averaged, &mut sink)?;
+
+    assert_eq!(n, 4);
+    assert_eq!(sink.as_read(), [3.0, 5.0, 7.0, 6.0]);
+    Ok(())
+}
+/// A source returning at most `max` items per read.
+struct Throttle<S> {
+    inner: S,
+    max: usize,
+}
+impl<S: Source<T>, T> Source<T> for Throttle<S> {
+    fn source(&mut self, into: &mut [T]) -> IO {
+        let n = into.len().min(self.max);
+        self.inner.source(&mut into[..n])
+    }
+}
+#[test]
+fn buffer_ensure_available() -> IO<()> {
+    let mut source = Throttle {
+        inner: Buffer::from_copy([1, 2, 3, 4, 5, 6, 7, 8, 9, 10u8]).as_source(),
+        max: 1,
+    };
+    let mut buf = Buffer::from_copy([0u8; 6]);
+
+    let mut headers = Vec::new();
+    while buf.ensure_available(&mut source, 4)? {
+        let mut header = [0u8; 4];
+        buf.copy_into(&mut header)?;
+        headers.push(header);
+    }
+
+    assert_eq!(headers, [[1, 2, 3, 4], [5, 6, 7, 8]]);
+    assert_eq!(buf.as_read(), [9, 10]);
+    assert!(!buf.ensure_available(&mut source, 7)?);
+    Ok(())
+}
+#[test]
+fn stream_replay() -> IO<()> {
+    let mut source = stream::Replay::new(vec![vec![1], vec![2, 3], vec![], vec![4, 5, 6]]);
+    let mut sink = Buffer::from_copy([0u8; 8]);
+
+    // The empty read ends the transfusion
+    let n = Buffer::from_copy([0u8; 4]).transfuse(&mut source, &mut sink)?;
+    assert_eq!(n, 3);
+    assert_eq!(sink.as_read(), [1, 2, 3]);
+
+    let mut dest = [0u8; 2];
+    assert_eq!(source.source(&mut dest)?, 2);
+    assert_eq!(dest, [4, 5]);
+    assert_eq!(source.source(&mut dest)?, 1);
+    assert_eq!(dest[0], 6);
+    assert_eq!(source.source(&mut dest)?, 0);
+    Ok(())
+}
+#[test]
+fn buffer_free_mut() -> IO<()> {
+    let mut buf = Buffer::from_copy(vec![0u8; 4]);
+    buf.copy_from(&[1, 2, 3, 4])?;
+    buf.copy_into(&mut [0u8; 1])?;
+
+    // Compacting is enough
+    assert_eq!(buf.free_mut(1).len(), 1);
+    assert_eq!(buf.as_read(), [2, 3, 4]);
+
+    // Growing is needed
+    assert_eq!(buf.free_mut(6).len(), 6);
+    assert_eq!(buf.len(), 9);
+    buf.read(Buffer::from_copy([5, 6u8]).as_source())?;
+    assert_eq!(buf.as_read(), [2, 3, 4, 5, 6]);
+    Ok(())
+}
+#[test]
+fn flow_try_unwrap() -> IO<()> {
+    let results = stream::Replay::new(vec![vec![
+        Ok(1u8),
+        Ok(2),
+        Err(io::ErrorKind::InvalidData),
+        Ok(3),
+    ]]);
+    let mut items = flow::TryUnwrapFlow.flow(results);
+
+    let mut sink = Buffer::from_copy([0u8; 4]);
+    assert_eq!(sink.read(&mut items)?, 2);
+    assert_eq!(sink.as_read(), [1, 2]);
+
+    let err = sink.read(&mut items).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    assert_eq!(sink.read(&mut items)?, 1);
+    assert_eq!(sink.as_read(), [1, 2, 3]);
+    assert_eq!(sink.read(&mut items)?, 0);
+    Ok(())
+}
+#[test]
+fn buffer_transfuse_accounting() -> IO<()> {
+    let mut source = Buffer::from_copy([1, 2, 3, 4, 5u8]).as_source();
+    let mut sink = Buffer::from_copy([0u8; 4]);
+    let mut buf = Buffer::from_copy([0u8; 3]);
+
+    let (read, written) = buf.transfuse_accounting(&mut source, &mut sink)?;
+
+    assert_eq!(read, 5);
+    assert_eq!(written, 4);
+    assert_eq!(buf.as_read(), [5]);
+    Ok(())
+}
+#[test]
+fn stream_deque() -> IO<()> {
+    use std::collections::VecDeque;
+
+    // Wrap the ring around, so the items are not contiguous
+    let mut deque = VecDeque::with_capacity(4);
+    deque.extend([0, 0, 1, 2u8]);
+    deque.drain(..2);
+    deque.extend([3, 4]);
+    assert!(!deque.as_slices().1.is_empty());
+
+    let mut source = stream::DequeSource::new(&deque);
+    let mut sink = Buffer::from_copy([0u8; 8]);
+    let n = Buffer::from_copy([0u8; 3]).transfuse(&mut source, &mut sink)?;
+    assert_eq!(n, 4);
+    assert_eq!(sink.as_read(), [1, 2, 3, 4]);
+    assert_eq!(deque.len(), 4);
+
+    let mut out = VecDeque::new();
+    let n = Buffer::from_copy([0u8; 3]).transfuse(&mut deque, &mut out)?;
+    assert_eq!(n, 4);
+    assert!(deque.is_empty());
+    assert_eq!(out, [1, 2, 3, 4]);
+    Ok(())
+}
+#[test]
+fn buffer_compare() -> IO<()> {
+    let mut buf = Buffer::from_copy(*b"xxGET / HTTP/1.1\r\n").as_source();
+    buf.copy_into(&mut [0u8; 2])?;
+
+    assert!(buf.starts_with(b"GET "));
+    assert!(!buf.starts_with(b"POST "));
+    assert!(buf.ends_with(b"\r\n"));
+    assert!(!buf.ends_with(b"\n\n"));
+
+    assert_eq!(buf.find(b" "), Some(3));
+    assert_eq!(buf.find(b"\r\n"), Some(14));
+    assert_eq!(buf.find(b"\n\r"), None);
+    assert_eq!(buf.find(b"xx"), None);
+    assert_eq!(buf.find(b""), Some(0));
+    Ok(())
+}
+#[test]
+fn flow_delta() -> IO<()> {
+    let values = Buffer::from_copy([10, 13, 13, 20i64]).as_source();
+
+    let mut deltas = Buffer::from_copy([0i64; 4]);
+    deltas.read(flow::DeltaFlow.flow(values))?;
+    assert_eq!(deltas.as_read(), [10, 3, 0, 7]);
+
+    let mut restored = Buffer::from_copy([0i64; 4]);
+    Buffer::from_copy([0i64; 3]).transfuse(flow::UnDeltaFlow.flow(deltas), &mut restored)?;
+    assert_eq!(restored.as_read(), [10, 13, 13, 20]);
+    Ok(())
+}
+#[test]
+fn buffer_sub_source() -> IO<()> {
+    let buf = Buffer::from_copy([1, 2, 3, 4, 5u8]).as_source();
+
+    let mut field = buf.sub_source(1..3)?;
+    let mut sink = Buffer::from_copy([0u8; 4]);
+    sink.read(&mut field)?;
+    assert_eq!(sink.as_read(), [2, 3]);
+    assert!(field.is_empty());
+    assert_eq!(buf.available(), 5);
+
+    let err = buf.sub_source(4..6).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    Ok(())
+}
+#[test]
+fn stream_route() -> IO<()> {
+    let mut route = stream::Route {
+        classify: |n: &u8| (n % 2) as usize,
+        sinks: vec![Buffer::from_copy([0u8; 4]), Buffer::from_copy([0u8; 4])],
+    };
+    let n = Buffer::from_copy([0u8; 3]).transfuse(
+        Buffer::from_copy([1, 2, 4, 3, 5, 6u8]).as_source(),
+        &mut route,
+    )?;
+    assert_eq!(n, 6);
+    assert_eq!(route.sinks[0].as_read(), [2, 4, 6]);
+    assert_eq!(route.sinks[1].as_read(), [1, 3, 5]);
+
+    let mut route = stream::Route {
+        classify: |n: &u8| *n as usize,
+        sinks: vec![Buffer::from_copy([0u8; 4])],
+    };
+    let err = route.sink(&[0, 1]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(route.sinks[0].as_read(), [0]);
+
+    // Classified once per item, run boundaries included
+    let mut calls = 0;
+    let mut route = stream::Route {
+        classify: |n: &u8| {
+            calls += 1;
+            (n % 2) as usize
+        },
+        sinks: vec![Buffer::from_copy([0u8; 4]), Buffer::from_copy([0u8; 4])],
+    };
+    assert_eq!(route.sink(&[1, 2, 4, 3, 5, 6])?, 6);
+    assert_eq!(route.sinks[0].as_read(), [2, 4, 6]);
+    assert_eq!(route.sinks[1].as_read(), [1, 3, 5]);
+    drop(route);
+    assert_eq!(calls, 6);
+
+    // Flushing reaches buffering sinks behind the route
+    let mut route = stream::Route {
+        classify: |n: &u8| (n % 2) as usize,
+        sinks: vec![
+            stream::BufSink::new(CountingSink::default(), 8),
+            stream::BufSink::new(CountingSink::default(), 8),
+        ],
+    };
+    assert_eq!(route.sink(&[1, 2, 4, 3, 5, 6])?, 6);
+    assert_eq!(route.sinks[0].inner().items, 0);
+    assert_eq!(route.sinks[1].inner().items, 0);
+    route.flush()?;
+    assert_eq!(route.sinks[0].inner().items, 3);
+    assert_eq!(route.sinks[1].inner().items, 3);
+    Ok(())
+}
+#[test]
+fn buffer_transfuse_blocking() -> IO<()> {
+    // A sink that is busy every other call, either way, and otherwise accepts one item
+    struct Busy<S> {
+        inner: S,
+        calls: usize,
+    }
+    impl<S: Sink<u8>> Sink<u8> for Busy<S> {
+        fn sink(&mut self, from: &[u8]) -> IO {
+            self.calls += 1;
+            match self.calls % 4 {
+                1 => Ok(0),
+                3 => Err(io::ErrorKind::WouldBlock.into()),
+                _ => self.inner.sink(&from[..1]),
+            }
+        }
+    }
+
+    let mut sink = Busy {
+        inner: Buffer::from_copy([0u8; 8]),
+        calls: 0,
+    };
+    let n = Buffer::from_copy([0u8; 2]).transfuse_blocking(
+        Buffer::from_copy([1, 2, 3, 4, 5u8]).as_source(),
+        &mut sink,
+        std::time::Duration::from_millis(1),
+    )?;
+    assert_eq!(n, 5);
+    assert_eq!(sink.inner.as_read(), [1, 2, 3, 4, 5]);
+    Ok(())
+}
+#[test]
+fn stream_files() -> IO<()> {
+    use std::{
+        cell::RefCell,
+        path::PathBuf,
+        rc::Rc,
+    };
+
+    let dir = std::env::temp_dir();
+    let paths: Vec<PathBuf> = ["a", "b"]
+        .iter()
+        .map(|name| dir.join(format!("bio-files-{}-{name}", std::process::id())))
+        .collect();
+    std::fs::write(&paths[0], b"hello, ")?;
+    std::fs::write(&paths[1], b"bio!")?;
+
+    let boundaries = Rc::new(RefCell::new(Vec::new()));
+    let mut source = stream::Files::new(paths.clone(), {
+        let boundaries = Rc::clone(&boundaries);
+        move |path| boundaries.borrow_mut().push(path.to_owned())
+    });
+    let mut sink = stream::Write(Vec::new());
+    let n = Buffer::from_copy([0u8; 4]).transfuse(&mut source, &mut sink);
+
+    let missing = dir.join(format!("bio-files-{}-missing", std::process::id()));
+    let mut failing = stream::Files::new(vec![missing], |_| ());
+    let err = failing.source(&mut [0u8; 4]).unwrap_err();
+
+    for path in &paths {
+        std::fs::remove_file(path)?;
+    }
+    assert_eq!(n?, 11);
+    assert_eq!(sink.0, b"hello, bio!");
+    assert_eq!(*boundaries.borrow(), paths);
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    Ok(())
+}
+#[test]
+fn buffer_overflow_safety() -> IO<()> {
+    // Pre-seed a running total close to overflowing
+    let mut buf = Buffer::from_copy([0u8; 2]);
+    let mut sink = Buffer::from_copy([0u8; 8]);
+    let total = transfuse_from(
+        false,
+        usize::MAX - 1,
+        &mut buf,
+        Buffer::from_copy([1, 2, 3u8]).as_source(),
+        &mut sink,
+    )?;
+    assert_eq!(total, usize::MAX);
+    assert_eq!(sink.as_read(), [1, 2, 3]);
+
+    // Zero-sized items allow spans at the far end of usize
+    let mut buf = Buffer::from_copy_with_span([(); usize::MAX], usize::MAX - 2, usize::MAX)?;
+    buf.compact();
+    assert_eq!(buf.span(), (0, 2));
+    assert_eq!(buf.free(), usize::MAX - 2);
+    Ok(())
+}
+#[test]
+fn flow_tee_to() -> IO<()> {
+    let source = stream::Read(b"archived".as_ref());
+    let mut teed = flow::TeeTo::new(source, stream::Write(Vec::new()));
+
+    let mut sink = Buffer::from_copy([0u8; 16]);
+    let n = Buffer::from_copy([0u8; 3]).transfuse(&mut teed, &mut sink)?;
+
+    assert_eq!(n, 8);
+    assert_eq!(sink.as_read(), b"archived");
+    assert_eq!(teed.side().0, b"archived");
+
+    let mut full =
+        flow::TeeToFlow(Buffer::from_copy([0u8; 4])).flow(stream::Read(b"archived".as_ref()));
+    let err = Buffer::from_copy([0u8; 3])
+        .transfuse(&mut full, &mut sink)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+    Ok(())
+}
+#[cfg(feature = "arrayvec")]
+#[test]
+fn stream_arrayvec() -> IO<()> {
+    let mut sink = arrayvec::ArrayVec::<u8, 4>::new();
+    assert_eq!(sink.sink(&[1, 2, 3])?, 3);
+    assert_eq!(sink.sink(&[4, 5, 6])?, 1);
+    assert_eq!(sink.sink(&[7])?, 0);
+    assert_eq!(sink.as_slice(), [1, 2, 3, 4]);
+
+    let mut dest = [0u8; 3];
+    assert_eq!(sink.source(&mut dest)?, 3);
+    assert_eq!(dest, [1, 2, 3]);
+    assert_eq!(sink.as_slice(), [4]);
+    Ok(())
+}
+#[test]
+fn buffer_replace_data() -> IO<()> {
+    let mut buf = Buffer::from_copy(vec![0u8; 4]);
+    buf.read(Buffer::from_copy([1, 2, 3u8]).as_source())?;
+
+    let old = buf.replace_data(vec![0u8; 2]);
+    assert_eq!(old, [1, 2, 3, 0]);
+    assert!(buf.is_empty());
+    assert_eq!(buf.span(), (0, 0));
+    assert_eq!(buf.free(), 2);
+
+    buf.read(Buffer::from_copy([7, 8, 9u8]).as_source())?;
+    assert_eq!(buf.as_read(), [7, 8]);
+    Ok(())
+}
+#[test]
+fn flow_json_array_items() -> IO<()> {
+    let json = br#" [{"a":1}, {"b":"]\"}"} ,{"c":[3]}] "#;
+    let mut items = flow::JsonArrayItemsFlow(4).flow(stream::Read(json.as_ref()));
+
+    let mut dest = vec![Buffer::from_copy(Vec::new()); 2];
+    assert_eq!(items.source(&mut dest)?, 2);
+    assert_eq!(dest[0].as_read(), br#"{"a":1}"#);
+    assert_eq!(dest[1].as_read(), br#"{"b":"]\"}"}"#);
+    assert_eq!(items.source(&mut dest)?, 1);
+    assert_eq!(dest[0].as_read(), br#"{"c":[3]}"#);
+    assert_eq!(items.source(&mut dest)?, 0);
+
+    for bad in [&b"[{]}]"[..], b"[1,,2]", b"{}", b"[1] 2"] {
+        let mut items = flow::JsonArrayItemsFlow(4).flow(stream::Read(bad));
+        let err = items
+            .source(&mut dest)
+            .and_then(|_| items.source(&mut dest));
+        assert_eq!(err.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+    let mut items = flow::JsonArrayItemsFlow(4).flow(stream::Read(b"[1, 2".as_ref()));
+    assert_eq!(items.source(&mut dest)?, 1);
+    let err = items.source(&mut dest).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    Ok(())
+}
+#[test]
+fn buffer_transfuse_map() -> IO<()> {
+    let source = Buffer::from_copy([1, 2, 3, 4, 5u8]).as_source();
+    let mut sink = Buffer::from_copy([0u8; 8]);
+
+    let mut buf = Buffer::from_copy([0u8; 3]);
+    let swap_u16 = |from: &[u8], into: &mut [u8]| {
+        let n = std::cmp::min(from.len(), into.len()) / 2 * 2;
+        for (pair, swapped) in from[..n].chunks(2).zip(into.chunks_mut(2)) {
+            swapped.copy_from_slice(&[pair[1], pair[0]]);
+        }
+        Ok(n)
+    };
+    let n = buf.transfuse_map(source, swap_u16, &mut sink)?;
+
+    assert_eq!(n, 4);
+    assert_eq!(sink.as_read(), [2, 1, 4, 3]);
+    // A trailing odd byte is left unmapped.
+    assert_eq!(buf.as_read(), [5]);
+    Ok(())
+}
+#[test]
+fn buffer_transfuse_map_sink_full() -> IO<()> {
+    let mut source = Buffer::from_copy([1, 2, 3, 4, 5, 6u8]).as_source();
+    let mut buf = Buffer::from_copy([0u8; 4]);
+    let double = |from: &[u8], into: &mut [u16]| {
+        for (item, mapped) in from.iter().zip(&mut *into) {
+            *mapped = u16::from(*item) * 2;
+        }
+        Ok(from.len())
+    };
+
+    // Items the sink cannot take stay in the buffer, unmapped, for the next call.
+    let mut sink = Buffer::from_copy([0u16; 3]);
+    assert_eq!(buf.transfuse_map(&mut source, double, &mut sink)?, 3);
+    assert_eq!(sink.as_read(), [2, 4, 6]);
+    assert_eq!(buf.as_read(), [4, 5, 6]);
+
+    let mut sink = Buffer::from_copy([0u16; 8]);
+    assert_eq!(buf.transfuse_map(&mut source, double, &mut sink)?, 3);
+    assert_eq!(sink.as_read(), [8, 10, 12]);
+    assert!(buf.is_empty());
+    Ok(())
+}
+#[test]
+fn stream_dyn_iterator() -> IO<()> {
+    let mut items = 0u8..3;
+    let source = &mut items as &mut dyn Iterator<Item = u8>;
+    let mut sink = Buffer::from_copy([0u8; 4]);
+
+    Buffer::from_copy([0u8; 2]).transfuse(source, &mut sink)?;
+    assert_eq!(sink.as_read(), [0, 1, 2]);
+    Ok(())
+}
+#[test]
+fn buffer_drain_while() -> IO<()> {
+    let mut buf = Buffer::from_copy([0, 0, 1, 2u8]).as_source();
+    let mut sink = CountingSink::default();
+
+    assert_eq!(buf.drain_while(|&b| b == 0, &mut sink)?, 2);
+    assert_eq!(buf.as_read(), [1, 2]);
+    assert_eq!(buf.drain_while(|&b| b == 0, &mut sink)?, 0);
+    assert_eq!(buf.as_read(), [1, 2]);
+    assert_eq!(sink.items, 2);
+    Ok(())
+}
+#[cfg(feature = "crossbeam")]
+#[test]
+fn stream_crossbeam_channel() -> IO<()> {
+    let (sender, receiver) = crossbeam_channel::bounded::<u32>(2);
+    let producer = std::thread::spawn(move || {
+        let source = Buffer::from_copy([1, 2, 3, 4, 5u32]).as_source();
+        Buffer::from_copy([0u32; 3]).transfuse(source, sender)
+    });
+
+    let mut sink = Buffer::from_copy([0u32; 8]);
+    let n = Buffer::from_copy([0u32; 2]).transfuse(receiver, &mut sink)?;
+    assert_eq!(producer.join().unwrap()?, 5);
+    assert_eq!(n, 5);
+    assert_eq!(sink.as_read(), [1, 2, 3, 4, 5]);
+    Ok(())
+}
+#[test]
+fn buffer_transfuse_inplace() -> IO<()> {
+    let source = Buffer::from_copy([1, 2, 3, 4, 5u8]).as_source();
+    let mut sink = Buffer::from_copy([0u8; 8]);
+
+    let increment = |items: &mut [u8]| items.iter_mut().for_each(|b| *b += 1);
+    let n = Buffer::from_copy([0u8; 2]).transfuse_inplace(source, increment, &mut sink)?;
+
+    assert_eq!(n, 5);
+    assert_eq!(sink.as_read(), [2, 3, 4, 5, 6]);
+    Ok(())
+}
+#[test]
+fn stream_bounded_vec() -> IO<()> {
+    let source = Buffer::from_copy([1, 2, 3, 4, 5, 6, 7u8]).as_source();
+    let mut sink = stream::BoundedVec {
+        data: Vec::new(),
+        cap: 5,
+    };
+
+    let err = Buffer::from_copy([0u8; 2])
+        .transfuse(source, &mut sink)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::OutOfMemory);
+    assert_eq!(sink.data, [1, 2, 3, 4, 5]);
+    Ok(())
+}
+#[test]
+fn buffer_from_reader() -> IO<()> {
+    let path = std::env::temp_dir().join(format!("bio-from-reader-{}", std::process::id()));
+    let content: Vec<u8> = (0..10_000u32).map(|i| i as u8).collect();
+    std::fs::write(&path, &content)?;
+
+    let buf = Buffer::from_reader(std::fs::File::open(&path)?);
+    std::fs::remove_file(&path)?;
+    let buf = buf?;
+    assert_eq!(buf.as_read(), content);
+    assert_eq!(buf.len(), content.len());
+    Ok(())
+}
+#[test]
+fn flow_lz77() -> IO<()> {
+    let input = b"abcabcabcabcxyzxyzxyz-abcabc";
+
+    let mut tokens = flow::Lz77Flow { window: 16 }.flow(stream::Read(input.as_ref()));
+    let mut compressed = vec![flow::Token::Literal(0); 32];
+    let count = tokens.source(&mut compressed)?;
+    compressed.truncate(count);
+    assert_eq!(
+        compressed[..4],
+        [
+            flow::Token::Literal(b'a'),
+            flow::Token::Literal(b'b'),
+            flow::Token::Literal(b'c'),
+            flow::Token::Match {
+                offset: 3,
+                length: 9
+            },
+        ]
+    );
+    assert!(count < input.len() / 2);
+
+    let tokens = Buffer::from_clone(compressed).as_source();
+    let mut sink = Buffer::from_copy([0u8; 64]);
+    Buffer::from_copy([0u8; 5])
+        .transfuse(flow::UnLz77Flow { window: 16 }.flow(tokens), &mut sink)?;
+    assert_eq!(sink.as_read(), input);
+
+    let bad = Buffer::from_clone([flow::Token::Match {
+        offset: 1,
+        length: 3,
+    }])
+    .as_source();
+    let err = flow::UnLz77Flow { window: 16 }
+        .flow(bad)
+        .source(&mut [0u8; 4])
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    let empty_match = Buffer::from_clone([
+        flow::Token::Literal(b'a'),
+        flow::Token::Match {
+            offset: 1,
+            length: 0,
+        },
+    ])
+    .as_source();
+    let mut unlz77 = flow::UnLz77Flow { window: 16 }.flow(empty_match);
+    let mut dest = [0u8; 4];
+    assert_eq!(unlz77.source(&mut dest[..1])?, 1);
+    let err = unlz77.source(&mut dest).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    Ok(())
+}
+#[test]
+fn buffer_shift_out() {
+    let mut buf = Buffer::from_copy([1, 2, 3, 4, 5u8]).as_source();
+    assert_eq!(buf.shift_out(2), 2);
+    assert_eq!(buf.as_read(), [3, 4, 5]);
+    assert_eq!(buf.shift_out(9), 3);
+    assert!(buf.is_empty());
+}
+#[test]
+fn stream_limit_read() -> IO<()> {
+    let mut sink = Buffer::from_copy([0u8; 8]);
+    let source = Buffer::from_copy([1, 2, 3, 4, 5u8]).as_source();
+    let mut limited = stream::LimitRead::new(source, 3, true);
+    Buffer::from_copy([0u8; 2]).transfuse(&mut limited, &mut sink)?;
+    assert_eq!(sink.as_read(), [1, 2, 3]);
+    assert_eq!(limited.read(), 3);
+
+    sink.clear();
+    let source = Buffer::from_copy([1, 2, 3, 4, 5u8]).as_source();
+    let limited = stream::LimitRead::new(source, 3, false);
+    let err = Buffer::from_copy([0u8; 2])
+        .transfuse(limited, &mut sink)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(sink.as_read(), [1, 2, 3]);
+
+    // The items within the limit come through before the error does.
+    let source = Buffer::from_copy([1, 2, 3, 4, 5, 6u8]).as_source();
+    let mut limited = stream::LimitRead::new(source, 5, false);
+    let mut dest = [0u8; 8];
+    assert_eq!(limited.source(&mut dest)?, 5);
+    assert_eq!(dest[..5], [1, 2, 3, 4, 5]);
+    let err = limited.source(&mut dest).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert!(limited.source(&mut dest).is_err());
+
+    sink.clear();
+    let source = Buffer::from_copy([1, 2, 3u8]).as_source();
+    Buffer::from_copy([0u8; 2]).transfuse(stream::LimitRead::new(source, 3, false), &mut sink)?;
+    assert_eq!(sink.as_read(), [1, 2, 3]);
+    Ok(())
+}
+#[test]
+fn flow_hex_dump() -> IO<()> {
+    let bytes = b"Hello, hexdump!\n\x00\x01\x02\x7f";
+    let dump = flow::HexDumpFlow { width: 8 }.flow(stream::Read(bytes.as_ref()));
+    let mut sink = stream::Write(Vec::new());
+    Buffer::from_copy([0u8; 7]).transfuse(dump, &mut sink)?;
+
+    let expected = concat!(
+        "00000000  48 65 6c 6c 6f 2c 20 68  |Hello, h|\n",
+        "00000008  65 78 64 75 6d 70 21 0a  |exdump!.|\n",
+        "00000010  00 01 02 7f              |....|\n",
+    );
+    assert_eq!(String::from_utf8(sink.0).unwrap(), expected);
+    Ok(())
+}
+#[test]
+fn buffer_checksum() -> IO<()> {
+    let mut buf = Buffer::from_copy([0u8; 12]);
+    assert_eq!(buf.checksum(), 0x811c_9dc5);
+
+    buf.read(stream::Read(b"foobar".as_ref()))?;
+    assert_eq!(buf.checksum(), 0xbf9c_f968);
+    assert_eq!(buf.checksum(), 0xbf9c_f968);
+
+    // Only the available region counts.
+    buf.shift_out(3);
+    buf.read(stream::Read(b"baz".as_ref()))?;
+    assert_eq!(
+        buf.checksum(),
+        Buffer::from_copy(*b"barbaz").as_source().checksum()
+    );
+    Ok(())
+}
+#[test]
+fn flow_coalesce() -> IO<()> {
+    let source = Throttle {
+        inner: Buffer::from_copy([1, 2, 3, 4, 5, 6, 7, 8, 9, 10u8]).as_source(),
+        max: 1,
+    };
+    let mut coalesced = flow::CoalesceFlow::<4>.flow(source);
+
+    let mut emissions = Vec::new();
+    let mut dest = [0u8; 8];
+    loop {
+        match coalesced.source(&mut dest)? {
+            0 => break,
+            n => emissions.push(dest[..n].to_vec()),
+        }
+    }
+    assert_eq!(emissions, [vec![1, 2, 3, 4], vec![5, 6, 7, 8], vec![9, 10]]);
+
+    // Nothing to coalesce to: reads pass through, rather than the source looking depleted.
+    let mut passed = flow::CoalesceFlow::<0>.flow(Throttle {
+        inner: Buffer::from_copy([1, 2, 3u8]).as_source(),
+        max: 2,
+    });
+    assert_eq!(passed.source(&mut dest)?, 2);
+    assert_eq!(passed.source(&mut dest)?, 1);
+    assert_eq!(passed.source(&mut dest)?, 0);
+    Ok(())
+}
+#[test]
+fn stream_ref_cell() -> IO<()> {
+    let shared = std::cell::RefCell::new(stream::Write(Vec::new()));
+    let mut buf = Buffer::from_copy([0u8; 3]);
+
+    // One branch tees into the shared sink, while another writes to it directly.
+    let teed = flow::TeeToFlow(&shared).flow(stream::Read(b"abc".as_ref()));
+    buf.transfuse(teed, CountingSink::default())?;
+    buf.transfuse(stream::Read(b"def".as_ref()), &shared)?;
+
+    assert_eq!(shared.into_inner().0, b"abcdef");
+    Ok(())
+}
+#[test]
+fn stream_buf_sink() -> IO<()> {
+    let mut sink = stream::BufSink::new(CountingSink::default(), 8);
+    for _ in 0..10 {
+        assert_eq!(sink.sink(b"abc")?, 3);
+    }
+    assert_eq!(sink.inner().calls, 4);
+    assert_eq!(sink.inner().items, 24);
+
+    sink.flush()?;
+    assert_eq!(sink.inner().calls, 5);
+    assert_eq!(sink.inner().items, 30);
+
+    // Long writes bypass the buffer.
+    assert_eq!(sink.sink(&[0; 20])?, 20);
+    assert_eq!(sink.inner().calls, 6);
+    Ok(())
+}
+#[test]
+fn stream_generated() -> IO<()> {
+    let squares = stream::Generated::new(|tx| {
+        for i in 1..=5u32 {
+            tx.send(i * i).unwrap();
+        }
+    });
+    let mut sink = Buffer::from_copy([0u32; 8]);
+    Buffer::from_copy([0u32; 2]).transfuse(squares, &mut sink)?;
+    assert_eq!(sink.as_read(), [1, 4, 9, 16, 25]);
+
+    let mut failing = stream::Generated::new(|tx| {
+        tx.send(1u32).unwrap();
+        panic!("generator failure");
+    });
+    let mut dest = [0u32; 4];
+    assert_eq!(failing.source(&mut dest)?, 1);
+    let err = failing.source(&mut dest).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::Other);
+    Ok(())
+}
+#[test]
+fn buffer_transfuse_deadline() -> IO<()> {
+    use std::time::{
+        Duration,
+        Instant,
+    };
+
+    let mut sleepy = Throttle {
+        inner: Buffer::from_copy([1, 2, 3, 4, 5, 6, 7, 8u8]).as_source(),
+        max: 1,
+    };
+    let mut source = stream::Delegate(|into: &mut [u8]| {
+        std::thread::sleep(Duration::from_millis(20));
+        sleepy.source(into)
+    });
+    let mut sink = Buffer::from_copy([0u8; 8]);
+    let mut buf = Buffer::from_copy([0u8; 4]);
+
+    let deadline = Instant::now() + Duration::from_millis(50);
+    let (n, cut_short) = buf.transfuse_deadline(&mut source, &mut sink, deadline)?;
+    assert!(cut_short);
+    assert!(n < 8);
+
+    let deadline = Instant::now() + Duration::from_secs(60);
+    let (m, cut_short) = buf.transfuse_deadline(&mut source, &mut sink, deadline)?;
+    assert!(!cut_short);
+    assert_eq!(n + m, 8);
+    assert_eq!(sink.as_read(), [1, 2, 3, 4, 5, 6, 7, 8]);
+    Ok(())
+}
+#[test]
+fn flow_strip_magic() -> IO<()> {
+    let strip = flow::StripMagicFlow {
+        magic: b"BIO1".to_vec(),
+    };
+    let mut sink = Buffer::from_copy([0u8; 8]);
+    let source = Throttle {
+        inner: stream::Read(b"BIO1data".as_ref()),
+        max: 3,
+    };
+    Buffer::from_copy([0u8; 3]).transfuse(strip.flow(source), &mut sink)?;
+    assert_eq!(sink.as_read(), b"data");
+
+    for (input, kind) in [
+        (b"BIO2data".as_ref(), io::ErrorKind::InvalidData),
+        (b"BI".as_ref(), io::ErrorKind::UnexpectedEof),
+    ] {
+        let err = strip
+            .flow(stream::Read(input))
+            .source(&mut [0u8; 8])
+            .unwrap_err();
+        assert_eq!(err.kind(), kind);
+    }
+    Ok(())
+}
+#[test]
+fn stream_shared_vec() -> IO<()> {
+    use std::sync::{
+        Arc,
+        Mutex,
+    };
+
+    let shared = Arc::new(Mutex::new(Vec::new()));
+    let producer = std::thread::spawn({
+        let shared = Arc::clone(&shared);
+        move || {
+            let source = Buffer::from_copy((0..100u8).collect::<Vec<_>>()).as_source();
+            Buffer::from_copy([0u8; 7]).transfuse(source, shared)
+        }
+    });
+    let consumer = std::thread::spawn({
+        let mut shared = Arc::clone(&shared);
+        move || -> IO<Vec<u8>> {
+            let mut received = stream::Write(Vec::new());
+            let mut buf = Buffer::from_copy([0u8; 5]);
+            while received.0.len() < 100 {
+                buf.transfuse(&mut shared, &mut received)?;
+                std::thread::yield_now();
+            }
+            Ok(received.0)
+        }
+    });
+
+    assert_eq!(producer.join().unwrap()?, 100);
+    assert_eq!(consumer.join().unwrap()?, (0..100).collect::<Vec<u8>>());
+    Ok(())
+}
+#[test]
+fn buffer_transfuse_all() -> IO<()> {
+    let parts = [b"con".as_ref(), b"cat".as_ref(), b"enated".as_ref()];
+    let mut sink = stream::Write(Vec::new());
+    let mut buf = Buffer::from_copy([0u8; 4]);
+
+    let n = buf.transfuse_all(parts.map(stream::Read), &mut sink)?;
+    assert_eq!(n, 12);
+    assert_eq!(sink.0, b"concatenated");
+
+    assert_eq!(
+        buf.transfuse_all(Vec::<stream::Read<&[u8]>>::new(), &mut sink)?,
+        0
+    );
+    Ok(())
+}
+#[test]
+fn flow_parity() -> IO<()> {
+    let data = [1, 2, 3, 4, 5, 6, 7u8];
+    let mut protected = stream::Write(Vec::new());
+    let parity = flow::ParityFlow::<3>.flow(Buffer::from_copy(data).as_source());
+    Buffer::from_copy([0u8; 2]).transfuse(parity, &mut protected)?;
+    assert_eq!(protected.0, [1, 2, 3, 1 ^ 2 ^ 3, 4, 5, 6, 4 ^ 5 ^ 6, 7, 7]);
+
+    let mut sink = Buffer::from_copy([0u8; 8]);
+    let source = Buffer::from_copy(protected.0.clone()).as_source();
+    Buffer::from_copy([0u8; 2]).transfuse(flow::CheckParityFlow::<3>.flow(source), &mut sink)?;
+    assert_eq!(sink.as_read(), data);
+
+    let mut corrupted = protected.0;
+    corrupted[5] ^= 0x10;
+    let source = Buffer::from_copy(corrupted).as_source();
+    sink.clear();
+    let err = Buffer::from_copy([0u8; 2])
+        .transfuse(flow::CheckParityFlow::<3>.flow(source), &mut sink)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    Ok(())
+}
+#[test]
+fn buffer_try_reserve() -> IO<()> {
+    let mut buf = Buffer::from_copy(vec![0u8; 4]);
+    buf.try_reserve(60)?;
+    assert!(buf.data.capacity() >= 64);
+    assert_eq!(buf.free_mut(64).len(), 64);
+
+    let err = buf.try_reserve(usize::MAX).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::OutOfMemory);
+    Ok(())
+}
+#[test]
+fn source_ext_lookahead() -> IO<()> {
+    let mut source = Buffer::from_copy([1, 2, 3, 4u8])
+        .as_source()
+        .lookahead::<3>();
+    assert_eq!(source.peek_window()?, [1, 2, 3]);
+    assert_eq!(source.peek_window()?, [1, 2, 3]);
+
+    let mut dest = [0u8; 1];
+    assert_eq!(source.source(&mut dest)?, 1);
+    assert_eq!(dest, [1]);
+    assert_eq!(source.peek_window()?, [2, 3, 4]);
+
+    let mut sink = Buffer::from_copy([0u8; 4]);
+    Buffer::from_copy([0u8; 2]).transfuse(&mut source, &mut sink)?;
+    assert_eq!(sink.as_read(), [2, 3, 4]);
+    assert_eq!(source.peek_window()?, []);
+    Ok(())
+}
+#[cfg(feature = "metrics")]
+#[test]
+fn buffer_compaction_stats() -> IO<()> {
+    let mut buf = Buffer::from_copy([0u8; 4]);
+    assert_eq!(buf.compaction_stats(), (0, 0));
+
+    // A sink taking 3 items at a time leaves 1 behind to shift after each cycle.
+    struct Sip;
+    impl Sink<u8> for Sip {
+        fn sink(&mut self, from: &[u8]) -> IO {
+            Ok(from.len().min(3))
+        }
+    }
+    let source = Buffer::from_copy([0u8; 12]).as_source();
+    buf.transfuse(source, Sip)?;
+    let (runs, moved) = buf.compaction_stats();
+    assert!(runs >= 3);
+    assert_eq!(moved, runs);
+
+    // The stats do not take part in comparisons.
+    let mut shifted = Buffer::from_copy([0u8; 4]);
+    shifted.copy_from(&[1, 2, 3])?;
+    shifted.shift_out(1);
+    shifted.compact();
+    assert_eq!(shifted.compaction_stats(), (1, 2));
+    let fresh = Buffer::from_copy_with_span([2, 3, 3, 0u8], 0, 2)?;
+    assert_eq!(shifted, fresh);
+    assert_eq!(shifted.cmp(&fresh), std::cmp::Ordering::Equal);
+    Ok(())
+}
+#[test]
+fn flow_number_lines() -> IO<()> {
+    for (input, expected) in [
+        (
+            b"first\nsecond\n".as_ref(),
+            b"1\tfirst\n2\tsecond\n".as_ref(),
+        ),
+        (b"first\n\nthird", b"1\tfirst\n2\t\n3\tthird"),
+    ] {
+        let numbered = flow::NumberLinesFlow { start: 1 }.flow(stream::Read(input));
+        let mut sink = stream::Write(Vec::new());
+        Buffer::from_copy([0u8; 3]).transfuse(numbered, &mut sink)?;
+        assert_eq!(sink.0, expected);
+    }
+    Ok(())
+}
+#[test]
+fn stream_once_and_empty() -> IO<()> {
+    let mut dest = [0u8; 2];
+
+    let mut once = std::iter::once(42u8);
+    assert_eq!(once.source(&mut [])?, 0);
+    assert_eq!(once.source(&mut dest)?, 1);
+    assert_eq!(dest[0], 42);
+    assert_eq!(once.source(&mut dest)?, 0);
+
+    assert_eq!(std::iter::empty::<u8>().source(&mut dest)?, 0);
+    Ok(())
+}
+#[test]
+fn stream_display_sink() -> IO<()> {
+    let source = Buffer::from_copy([1, 2, 3u32]).as_source();
+    let mut sink = stream::DisplaySink::new(String::new(), ",");
+    Buffer::from_copy([0u32; 2]).transfuse(source, &mut sink)?;
+    assert_eq!(sink.into_inner(), "1,2,3");
+    Ok(())
+}
+#[test]
+fn buffer_copy_large() -> IO<()> {
+    let data: Vec<u8> = (0..1 << 20).map(|i: u32| (i * 7 + i / 256) as u8).collect();
+    let mut buf = Buffer::from_copy(vec![0u8; data.len()]);
+    assert_eq!(buf.copy_from(&data)?, data.len());
+    assert_eq!(buf.as_read(), data);
+
+    let mut out = vec![0u8; data.len()];
+    assert_eq!(buf.copy_into(&mut out)?, data.len());
+    assert_eq!(out, data);
+    Ok(())
+}
+#[test]
+fn flow_until_terminator() -> IO<()> {
+    let input = b"HELO\r\nMAIL\r\n\r\nQUIT";
+    let framing = |strict| flow::UntilTerminatorFlow {
+        term: b"\r\n".to_vec(),
+        strict,
+    };
+    let source = || Throttle {
+        inner: stream::Read(input.as_ref()),
+        max: 5,
+    };
+
+    let mut messages = framing(false).flow(source());
+    let mut dest = vec![Buffer::from_copy(Vec::new()); 8];
+    let n = messages.source(&mut dest)?;
+    let framed: Vec<&[u8]> = dest[..n].iter().map(|m| m.as_read()).collect();
+    assert_eq!(framed, [&b"HELO"[..], b"MAIL", b"", b"QUIT"]);
+    assert_eq!(messages.source(&mut dest)?, 0);
+
+    let mut messages = framing(true).flow(source());
+    assert_eq!(messages.source(&mut dest[..3])?, 3);
+    let err = messages.source(&mut dest).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    Ok(())
+}
+#[test]
+fn buffer_transfuse_complete() -> IO<()> {
+    let source = Buffer::from_copy([1, 2, 3, 4, 5u8]).as_source();
+    let sink = Buffer::from_copy([0u8; 4]);
+
+    let result = Buffer::from_copy([0u8; 3]).transfuse_complete(source, sink)?;
+    assert_eq!(result.total, 4);
+    assert_eq!(result.sink.as_read(), [1, 2, 3, 4]);
+    assert_eq!(result.leftover, [5]);
+    assert!(result.source.is_empty());
+    Ok(())
+}
+#[test]
+fn source_ext_cycle() -> IO<()> {
+    let mut source = Buffer::from_copy([1, 2u8]).as_source().cycle();
+    let mut buf = Buffer::from_copy([0u8; 5]);
+    assert!(buf.ensure_available(&mut source, 5)?);
+    assert_eq!(buf.as_read(), [1, 2, 1, 2, 1]);
+
+    let mut empty = stream::Cycle::new(std::iter::empty::<u8>());
+    assert_eq!(empty.source(&mut [0u8; 2])?, 0);
+    Ok(())
+}
+#[test]
+fn buffer_vectored() -> IO<()> {
+    let mut buf = Buffer::from_copy([0u8; 6]);
+    let mut sources = [
+        Buffer::from_copy(&[1, 2u8][..]).as_source(),
+        Buffer::from_copy(&[3, 4, 5, 6, 7u8][..]).as_source(),
+    ];
+    assert_eq!(buf.read_vectored(&mut sources)?, 6);
+    assert_eq!(buf.as_read(), [1, 2, 3, 4, 5, 6]);
+    assert_eq!(sources[1].as_read(), [7]);
+
+    let mut sinks = [Buffer::from_copy([0u8; 4]), Buffer::from_copy([0u8; 4])];
+    assert_eq!(buf.write_vectored(&mut sinks)?, 6);
+    assert_eq!(sinks[0].as_read(), [1, 2, 3, 4]);
+    assert_eq!(sinks[1].as_read(), [5, 6]);
+    assert_eq!(buf.write_vectored(&mut sinks)?, 0);
+    Ok(())
+}
+#[test]
+fn flow_flatten() -> IO<()> {
+    let chunks = std::collections::VecDeque::from([
+        Buffer::from_copy(vec![1, 2, 3u8]).as_source(),
+        Buffer::from_copy(vec![]),
+        Buffer::from_copy(vec![4, 5]).as_source(),
+    ]);
+    let mut sink = Buffer::from_copy([0u8; 8]);
+    Buffer::from_copy([0u8; 2]).transfuse(flow::FlattenFlow.flow(chunks), &mut sink)?;
+    assert_eq!(sink.as_read(), [1, 2, 3, 4, 5]);
+    Ok(())
+}
+#[test]
+fn flow_flatten_each_consecutive() -> IO<()> {
+    let chunks = flow::EachConsecutiveFlow(Buffer::from_copy([0u8; 3]))
+        .flow(Buffer::from_copy([1, 2, 3, 4, 5, 6u8]).as_source());
+    let mut flat = flow::FlattenFlow.flow(chunks);
+
+    // Narrower than a chunk, so each one gets drained across two reads.
+    let mut got = vec![];
+    let mut dest = [0u8; 2];
+    loop {
+        let n = flat.source(&mut dest)?;
+        if n == 0 {
+            break;
+        }
+        got.extend_from_slice(&dest[..n]);
+    }
+    assert_eq!(got, [1, 2, 3, 4, 5, 6]);
+    Ok(())
+}
+#[test]
+fn stream_lines() -> IO<()> {
+    let text = "first\r\nsecond\n\nthird";
+    let lines = stream::Lines(io::BufReader::new(text.as_bytes()));
+    let mut sink = Buffer::from_clone(<[String; 4]>::default());
+    Buffer::from_clone(<[String; 2]>::default()).transfuse(lines, &mut sink)?;
+    assert_eq!(sink.as_read(), ["first", "second", "", "third"]);
+
+    let mut invalid = stream::Lines(io::BufReader::new(b"ok\n\xff\n".as_ref()));
+    let mut dest = <[String; 2]>::default();
+    let err = invalid.source(&mut dest).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    Ok(())
+}
+#[test]
+fn stream_expect() -> IO<()> {
+    let mut expect = stream::Expect::new(vec![1, 2, 3, 4u8]);
+    let source = Buffer::from_copy([1, 2, 3, 4u8]).as_source();
+    Buffer::from_copy([0u8; 3]).transfuse(source, &mut expect)?;
+    expect.finish()?;
+
+    let mut expect = stream::Expect::new(vec![1, 2, 3, 4u8]);
+    let source = Buffer::from_copy([1, 2, 9, 4u8]).as_source();
+    let err = Buffer::from_copy([0u8; 3])
+        .transfuse(source, &mut expect)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "item #2: expected 3, got 9");
+
+    let mut expect = stream::Expect::new(vec![1, 2u8]);
+    expect.sink(&[1])?;
+    assert_eq!(
+        expect.finish().unwrap_err().kind(),
+        io::ErrorKind::InvalidData
+    );
+    Ok(())
+}
+#[test]
+fn buffer_transfuse_drain_first() -> IO<()> {
+    struct Sip(Vec<u8>);
+    impl Sink<u8> for Sip {
+        fn sink(&mut self, from: &[u8]) -> IO {
+            let n = from.len().min(2);
+            self.0.extend_from_slice(&from[..n]);
+            Ok(n)
+        }
+    }
+    let data = [1, 2, 3, 4, 5, 6, 7, 8u8];
+
+    // Records the free room offered to the source on each read.
+    let mut reads = Vec::new();
+    let mut sink = Sip(Vec::new());
+    let mut inner = Buffer::from_copy(data).as_source();
+    let source = stream::Delegate(|into: &mut [u8]| {
+        reads.push(into.len());
+        inner.source(into)
+    });
+    assert_eq!(Buffer::from_copy([0u8; 4]).transfuse(source, &mut sink)?, 8);
+    assert_eq!(sink.0, data);
+    assert_eq!(reads, [4, 2, 2, 2]);
+
+    let mut reads = Vec::new();
+    let mut sink = Sip(Vec::new());
+    let mut inner = Buffer::from_copy(data).as_source();
+    let source = stream::Delegate(|into: &mut [u8]| {
+        reads.push(into.len());
+        inner.source(into)
+    });
+    let n = Buffer::from_copy([0u8; 4]).transfuse_drain_first(source, &mut sink)?;
+    assert_eq!(n, 8);
+    assert_eq!(sink.0, data);
+    assert_eq!(reads, [4, 4, 4]);
+    Ok(())
+}
+#[test]
+fn flow_varint() -> IO<()> {
+    let values = [0, 1, 127, 128, 300, 16_384, u32::MAX as u64, u64::MAX];
+    let mut encoded = stream::Write(Vec::new());
+    let encode = flow::VarintEncodeFlow.flow(Buffer::from_copy(values).as_source());
+    Buffer::from_copy([0u8; 3]).transfuse(encode, &mut encoded)?;
+    assert_eq!(encoded.0[..6], [0x00, 0x01, 0x7f, 0x80, 0x01, 0xac]);
+
+    let mut decoded = Buffer::from_copy([0u64; 8]);
+    let decode = flow::VarintFlow.flow(stream::Read(encoded.0.as_slice()));
+    Buffer::from_copy([0u64; 3]).transfuse(decode, &mut decoded)?;
+    assert_eq!(decoded.as_read(), values);
+
+    for (bytes, kind) in [
+        (&[0xff; 11][..], io::ErrorKind::InvalidData),
+        (
+            &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02],
+            io::ErrorKind::InvalidData,
+        ),
+        (&[0x80, 0x80], io::ErrorKind::UnexpectedEof),
+    ] {
+        let err = flow::VarintFlow
+            .flow(stream::Read(bytes))
+            .source(&mut [0u64; 4])
+            .unwrap_err();
+        assert_eq!(err.kind(), kind);
+    }
+    Ok(())
+}
+#[test]
+fn buffer_compact_to_front() -> IO<()> {
+    // Copying compaction, as in `Buffer::from_copy()`.
+    let mut buf = Buffer::from_copy([1, 2, 3, 4, 5u8]).as_source();
+    buf.shift_out(2);
+    buf.compact();
+    assert_eq!(buf.span(), (0, 3));
+    assert_eq!(buf.as_read(), [3, 4, 5]);
+
+    // Moving compaction, as in `Buffer::from()`, for items that are not `Copy`.
+    let mut buf = Buffer::from(<[String; 4]>::default());
+    let mut words = std::collections::VecDeque::from(["a", "b", "c"].map(String::from));
+    buf.read(&mut words)?;
+    buf.shift_out(1);
+    buf.compact();
+    assert_eq!(buf.span(), (0, 2));
+    assert_eq!(buf.as_read(), ["b", "c"]);
+    Ok(())
+}
+#[test]
+fn stream_tuples() -> IO<()> {
+    let mut sinks = (stream::Write(Vec::new()), CountingSink::default());
+    let source = Buffer::from_copy([1, 2, 3, 4, 5u8]).as_source();
+    assert_eq!(
+        Buffer::from_copy([0u8; 2]).transfuse(source, &mut sinks)?,
+        5
+    );
+    assert_eq!(sinks.0 .0, [1, 2, 3, 4, 5]);
+    assert_eq!(sinks.1.items, 5);
+
+    // The sink accepting the fewest items sets the pace.
+    let mut lagging = (CountingSink::default(), Buffer::from_copy([0u8; 2]));
+    assert_eq!(lagging.sink(&[1, 2, 3])?, 2);
+    assert_eq!(lagging.0.items, 3);
+    assert_eq!(lagging.1.as_read(), [1, 2]);
+
+    let mut leading = (Buffer::from_copy([0u8; 2]), CountingSink::default());
+    assert_eq!(leading.sink(&[1, 2, 3])?, 2);
+    assert_eq!(leading.1.items, 2);
+    assert_eq!(leading.sink(&[3])?, 0);
+    Ok(())
+}
+#[test]
+fn stream_delegate() -> IO<()> {
+    let mut next = 0u32;
+    let counter = stream::Delegate(|dest: &mut [u32]| -> IO {
+        let n = dest.len().min(2);
+        for cell in &mut dest[..n] {
+            next += 1;
+            *cell = next;
+        }
+        Ok(n)
+    });
+    let mut sink = Buffer::from_copy([0u32; 5]);
+    Buffer::from_copy([0u32; 3]).transfuse(counter, &mut sink)?;
+    assert_eq!(sink.as_read(), [1, 2, 3, 4, 5]);
+    Ok(())
+}
+#[test]
+fn buffer_transfuse_with_hook() -> IO<()> {
+    let source = Buffer::from_copy([1, 2, 3, 4, 5u8]).as_source();
+    let mut sink = Buffer::from_copy([0u8; 8]);
+
+    let mut cycles = 0;
+    let mut buf = Buffer::from_copy([0u8; 2]);
+    let n = buf.transfuse_with_hook(source, &mut sink, |buf| {
+        assert_eq!(buf.span().0, 0);
+        cycles += 1;
+    })?;
+    assert_eq!(n, 5);
+    assert_eq!(sink.as_read(), [1, 2, 3, 4, 5]);
+    // Three cycles move the items, and a last one finds the source depleted.
+    assert_eq!(cycles, 4);
+    Ok(())
+}
+#[test]
+fn stream_delegate_sink() -> IO<()> {
+    let mut sum = 0u32;
+    let mut buf = Buffer::from_copy([10, 20, 30, 40u8]).as_source();
+    let n = buf.write(stream::DelegateSink(|from: &[u8]| -> IO {
+        sum += from.iter().map(|&b| b as u32).sum::<u32>();
+        Ok(from.len())
+    }))?;
+    assert_eq!(n, 4);
+    assert_eq!(sum, 100);
+    assert!(buf.is_empty());
+    Ok(())
+}
+#[cfg(feature = "tokio")]
+#[test]
+fn stream_block_on() -> IO<()> {
+    let runtime = tokio::runtime::Builder::new_current_thread().build()?;
+    let source = stream::BlockOn {
+        inner: b"async bytes".as_ref(),
+        handle: runtime.handle().clone(),
+    };
+    let mut sink = stream::Write(Vec::new());
+    Buffer::from_copy([0u8; 4]).transfuse(source, &mut sink)?;
+    assert_eq!(sink.0, b"async bytes");
+    Ok(())
+}
+#[test]
+fn flow_csv_fields() -> IO<()> {
+    let csv = b"name,quote\r\nbio,\"a, b\"\n\"say \"\"hi\"\"\",\n";
+    let fields = flow::CsvFieldsFlow {
+        delimiter: b',',
+        quote: b'"',
+    };
+    let source = Throttle {
+        inner: stream::Read(csv.as_ref()),
+        max: 3,
+    };
+    let mut parsed = fields.flow(source);
+
+    let mut dest = vec![flow::CsvField::default(); 4];
+    let mut records = vec![vec![]];
+    loop {
+        let n = parsed.source(&mut dest)?;
+        if n == 0 {
+            break;
+        }
+        for field in &dest[..n] {
+            records
+                .last_mut()
+                .unwrap()
+                .push(field.data.as_str()?.to_owned());
+            if field.end_of_record {
+                records.push(vec![]);
+            }
+        }
+    }
+    assert_eq!(
+        records,
+        [
+            vec!["name", "quote"],
+            vec!["bio", "a, b"],
+            vec!["say \"hi\"", ""],
+            vec![],
+        ]
+    );
+
+    let err = fields
+        .flow(stream::Read(b"a,\"open".as_ref()))
+        .source(&mut dest)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    Ok(())
+}
+#[test]
+fn flow_filter() -> IO<()> {
+    let evens = flow::FilterFlow {
+        pred: |x: &u32| x.is_multiple_of(2),
+        capacity: 3,
+    };
+    let mut source = evens.flow(Buffer::from_copy([1, 2, 3, 4, 5, 6, 7, 8]).as_source());
+
+    let mut got = vec![];
+    let mut dest = [0; 2];
+    loop {
+        let n = source.source(&mut dest)?;
+        if n == 0 {
+            break;
+        }
+        assert!(n <= 2);
+        got.extend_from_slice(&dest[..n]);
+    }
+    assert_eq!(got, [2, 4, 6, 8]);
+    Ok(())
+}
+#[test]
+fn buffer_reader() -> IO<()> {
+    let mut buf = Buffer::from_copy([1, 2, 3, 4, 5]).as_source();
+    let mut out = Buffer::from_copy([0; 8]);
+
+    assert_eq!(out.read(buf.reader().take(2))?, 2);
+    assert_eq!(out.as_read(), [1, 2]);
+    assert_eq!(buf.available(), 3);
+    assert_eq!(buf.as_read(), [3, 4, 5]);
+    Ok(())
+}
+#[test]
+fn flow_take() -> IO<()> {
+    let mut source = Buffer::from_copy([1, 2, 3, 4, 5]).as_source().take(3);
+    let mut dest = [0; 10];
+    assert_eq!(source.source(&mut dest)?, 3);
+    assert_eq!(dest[..3], [1, 2, 3]);
+    assert_eq!(source.source(&mut dest)?, 0);
+
+    let mut source = flow::TakeFlow(3).flow(Buffer::from_copy([1, 2, 3, 4, 5]).as_source());
+    assert_eq!(source.source(&mut dest[..2])?, 2);
+    assert_eq!(source.source(&mut dest)?, 1);
+    assert_eq!(dest[0], 3);
+    assert_eq!(source.source(&mut dest)?, 0);
+    Ok(())
+}
+#[test]
+fn stream_rotating_files() -> IO<()> {
+    let prefix = format!("bio-rotating-{}", std::process::id());
+    let mut sink = stream::RotatingFiles::new(std::env::temp_dir(), prefix, 4);
+
+    let data: Vec<u8> = (b'a'..=b'j').collect();
+    let mut source = Buffer::from_copy(data.clone()).as_source();
+    let n = Buffer::from_copy([0u8; 3]).transfuse(&mut source, &mut sink);
+    let sunk = sink.sink(b"")?;
+    sink.flush()?;
+
+    let paths: Vec<_> = (0..sink.files()).map(|i| sink.path(i)).collect();
+    let contents = paths.iter().map(std::fs::read).collect::<IO<Vec<_>>>();
+    for path in &paths {
+        std::fs::remove_file(path)?;
+    }
+
+    assert_eq!(n?, 10);
+    assert_eq!(sunk, 0);
+    let contents = contents?;
+    let sizes: Vec<_> = contents.iter().map(Vec::len).collect();
+    assert_eq!(sizes, [4, 4, 2]);
+    assert_eq!(contents.concat(), data);
+    Ok(())
+}
+#[test]
+fn stream_owned() -> IO<()> {
+    let mut vec = stream::Owned::new(vec![1u8, 2, 3, 4, 5]);
+    let mut dest = [0; 3];
+    assert_eq!(vec.source(&mut dest)?, 3);
+    assert_eq!(vec.source(&mut dest)?, 2);
+    assert_eq!(dest[..2], [4, 5]);
+    assert_eq!(vec.source(&mut dest)?, 0);
+
+    let mut array = stream::Owned::new([0u8; 4]);
+    let n = Buffer::from_copy([0u8; 3]).transfuse(stream::Owned::new([9, 8, 7, 6, 5]), &mut array);
+    assert_eq!(n?, 4);
+    assert_eq!(array.data, [9, 8, 7, 6]);
+    assert_eq!(array.sink(&[1])?, 0);
+
+    let mut boxed = stream::Owned::new(vec![0u8; 4].into_boxed_slice());
+    assert_eq!(boxed.sink(&[1, 2])?, 2);
+    boxed.pos = 1;
+    assert_eq!(boxed.source(&mut dest)?, 3);
+    assert_eq!(dest, [2, 0, 0]);
+    Ok(())
+}
+#[test]
+fn flow_chain() -> IO<()> {
+    let mut source = Buffer::from_copy([1, 2])
+        .as_source()
+        .chain(Buffer::from_copy([3, 4]).as_source());
+    let mut dest = [0; 4];
+    assert_eq!(source.source(&mut dest)?, 4);
+    assert_eq!(dest, [1, 2, 3, 4]);
+    assert_eq!(source.source(&mut dest)?, 0);
+
+    let mut source = stream::Owned::new([1, 2, 3]).chain(stream::Owned::new([4]));
+    let mut dest = [0; 2];
+    assert_eq!(source.source(&mut dest)?, 2);
+    assert_eq!(source.source(&mut dest)?, 2);
+    assert_eq!(dest, [3, 4]);
+    Ok(())
+}
+#[test]
+fn flow_cobs() -> IO<()> {
+    fn run<F: Flow<u8, u8>>(flow: F, input: &[u8]) -> IO<Vec<u8>> {
+        let source = Throttle {
+            inner: stream::Owned::new(input.to_vec()),
+            max: 3,
+        };
+        let mut sink = stream::Write(Vec::new());
+        Buffer::from_copy([0u8; 5]).transfuse(flow.flow(source), &mut sink)?;
+        Ok(sink.0)
+    }
+
+    let encoded = run(flow::CobsEncodeFlow, &[0x11, 0x22, 0x00, 0x33])?;
+    assert_eq!(encoded, [0x03, 0x11, 0x22, 0x02, 0x33, 0x00]);
+    assert_eq!(run(flow::CobsEncodeFlow, &[])?, [0x01, 0x00]);
+
+    let full: Vec<u8> = (1..=254).collect();
+    let encoded = run(flow::CobsEncodeFlow, &full)?;
+    assert_eq!(encoded.len(), 256);
+    assert_eq!((encoded[0], encoded[255]), (0xff, 0x00));
+
+    let payload: Vec<u8> = [&[0, 0, 7][..], &full, &[0, 9, 0]].concat();
+    let encoded = run(flow::CobsEncodeFlow, &payload)?;
+    assert!(!encoded[..encoded.len() - 1].contains(&0));
+    assert_eq!(run(flow::CobsDecodeFlow, &encoded)?, payload);
+
+    let err = run(flow::CobsDecodeFlow, &[0x03, 0x11, 0x00]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    let err = run(flow::CobsDecodeFlow, &[0x03, 0x11, 0x22]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    Ok(())
+}
+#[test]
+fn flow_enumerate() -> IO<()> {
+    let mut source = flow::EnumerateFlow.flow(Throttle {
+        inner: stream::Owned::new(*b"abc"),
+        max: 2,
+    });
+    let mut dest = [(0, 0u8); 4];
+    assert_eq!(source.source(&mut dest)?, 2);
+    assert_eq!(dest[..2], [(0, 97), (1, 98)]);
+    assert_eq!(source.source(&mut dest)?, 1);
+    assert_eq!(dest[0], (2, 99));
+    assert_eq!(source.source(&mut dest)?, 0);
+
+    let mut source = stream::Owned::new([5, 6]).enumerate();
+    assert_eq!(source.source(&mut dest[..1])?, 1);
+    assert_eq!(source.source(&mut dest)?, 1);
+    assert_eq!(dest[0], (1, 6));
+    Ok(())
+}
+#[test]
+fn buffer_split_off() -> IO<()> {
+    let mut buf = Buffer::from_copy_with_span(vec![9, 1, 2, 3, 4, 5, 0, 0], 1, 6)?;
+
+    let mut tail = buf.split_off(2);
+    assert_eq!(buf.as_read(), [1, 2]);
+    assert_eq!(tail.as_read(), [3, 4, 5]);
+
+    let mut sink = stream::Owned::new([0; 3]);
+    assert_eq!(tail.write(&mut sink)?, 3);
+    assert_eq!(sink.data, [3, 4, 5]);
+    assert_eq!(buf.available(), 2);
+    assert!(buf.split_off(7).is_empty());
+    Ok(())
+}
+#[test]
+fn stream_scheduled() -> IO<()> {
+    let inner = stream::Owned::new([1u8, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+    let mut source = stream::Scheduled::new(inner, vec![1, 3, 0, 2]);
+
+    let mut sizes = vec![];
+    let mut got = vec![];
+    let mut dest = [0u8; 4];
+    for _ in 0..8 {
+        let n = source.source(&mut dest)?;
+        sizes.push(n);
+        got.extend_from_slice(&dest[..n]);
+    }
+    assert_eq!(sizes, [1, 3, 0, 2, 1, 3, 0, 0]);
+    assert_eq!(got, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+
+    let mut uncapped = stream::Scheduled::new(stream::Owned::new([1u8, 2, 3]), vec![]);
+    assert_eq!(uncapped.source(&mut dest)?, 3);
+    Ok(())
+}
+#[test]
+fn buffer_transfuse_long_stream() -> IO<()> {
+    // Half a million rounds through the buffer, enough to overflow a recursive
+    // implementation's stack in debug builds.
+    let source = stream::Owned::new(vec![7u8; 4 << 20]);
+    let mut sink = CountingSink::default();
+    let n = Buffer::from_copy([0u8; 8]).transfuse(source, &mut sink)?;
+    assert_eq!(n, 4 << 20);
+    assert_eq!(sink.items, 4 << 20);
+    assert_eq!(sink.calls, (4 << 20) / 8);
+    Ok(())
+}
+#[test]
+fn buffer_transfuse_until_item() -> IO<()> {
+    let mut source = Throttle {
+        inner: stream::Owned::new([1u8, 2, 3, 4, 5, 0xff, 6, 0xff, 7]),
+        max: 4,
+    };
+    let mut buf = Buffer::from_copy([0u8; 4]);
+    let mut sink = Buffer::from_copy([0u8; 16]);
+    let n = buf.transfuse_until_item(&mut source, &mut sink, |&b| b == 0xff)?;
+    assert_eq!(n, 5);
+    assert_eq!(sink.as_read(), [1, 2, 3, 4, 5]);
+    assert_eq!(buf.as_read(), [0xff, 6, 0xff]);
+    assert_eq!(source.source(&mut [0; 4])?, 1);
+
+    let mut buf = Buffer::from_copy([0u8; 4]);
+    let mut sink = Buffer::from_copy([0u8; 16]);
+    let source = stream::Owned::new([1u8, 2, 3, 4, 5]);
+    assert_eq!(
+        buf.transfuse_until_item(source, &mut sink, |&b| b == 0xff)?,
+        5
+    );
+    assert!(buf.is_empty());
+    Ok(())
+}
+#[cfg(feature = "flate2")]
+#[test]
+fn flow_deflate() -> IO<()> {
+    let text: Vec<u8> = (0..20_000u32)
+        .flat_map(|i| format!("line {} of {}\n", i % 97, i % 13).into_bytes())
+        .collect();
+
+    let compressed = flow::DeflateFlow { level: 6 }.flow(Throttle {
+        inner: stream::Owned::new(text.clone()),
+        max: 1000,
+    });
+    let mut packed = stream::Write(Vec::new());
+    Buffer::from_copy([0u8; 512]).transfuse(compressed, &mut packed)?;
+    assert!(packed.0.len() < text.len() / 10);
+
+    let decompressed = flow::InflateFlow.flow(Throttle {
+        inner: stream::Owned::new(packed.0.clone()),
+        max: 100,
+    });
+    let mut unpacked = stream::Write(Vec::new());
+    Buffer::from_copy([0u8; 512]).transfuse(decompressed, &mut unpacked)?;
+    assert_eq!(unpacked.0, text);
+
+    let truncated = &packed.0[..packed.0.len() / 2];
+    let mut sink = stream::Write(Vec::new());
+    let err = Buffer::from_copy([0u8; 512])
+        .transfuse(
+            flow::InflateFlow.flow(stream::Owned::new(truncated)),
+            &mut sink,
+        )
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    Ok(())
+}
+/// Collects into `got` as many items per write as `schedule` says, then all of them.
+fn scheduled_sink(schedule: Vec<usize>, got: &mut Vec<u8>) -> impl Sink<u8> + '_ {
+    let mut schedule = schedule.into_iter();
+    stream::DelegateSink(move |from: &[u8]| {
+        let n = std::cmp::min(schedule.next().unwrap_or(usize::MAX), from.len());
+        got.extend_from_slice(&from[..n]);
+        Ok(n)
+    })
+}
+#[test]
+fn buffer_transfuse_full_buffer_is_not_eof() -> IO<()> {
+    let items = [1u8, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+
+    // The sink refuses the first write, so the buffer is still full on the next cycle,
+    // and the source gets nowhere to read into: that must not count as it being depleted.
+    let mut got = Vec::new();
+    let n = Buffer::from_copy([0u8; 4]).transfuse(
+        stream::Owned::new(items),
+        scheduled_sink(vec![0, 2], &mut got),
+    )?;
+    assert_eq!(n, 10);
+    assert_eq!(got, items);
+
+    // A sink refusing everything stops the transfusion with a full buffer; once the sink
+    // drains again, a second transfuse picks up where the first stopped.
+    let mut got = Vec::new();
+    let mut source = stream::Owned::new(items);
+    let mut buf = Buffer::from_copy([0u8; 4]);
+    let n = buf.transfuse(&mut source, scheduled_sink(vec![0, 0], &mut got))?;
+    assert_eq!((n, buf.available()), (0, 4));
+    assert_eq!(
+        buf.transfuse(&mut source, scheduled_sink(vec![], &mut got))?,
+        10
+    );
+    assert_eq!(got, items);
+    Ok(())
+}
+#[test]
+fn dyn_buffer_transfuse_full_buffer_is_not_eof() -> IO<()> {
+    let items = [1u8, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+    let mut got = Vec::new();
+    let n = DynBuffer::from_copy([0u8; 4]).transfuse(
+        stream::Owned::new(items),
+        scheduled_sink(vec![0, 2], &mut got),
+    )?;
+    assert_eq!(n, 10);
+    assert_eq!(got, items);
+    Ok(())
+}
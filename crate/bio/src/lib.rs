@@ -7,11 +7,14 @@ pub use buffer3::{
     Buffer,
     CompactStrategy,
     CopyStrategy,
+    DynBuffer,
     Flow,
     SClone,
     SCopy,
     SNone,
     Sink,
     Source,
+    SourceExt,
+    TransfuseResult,
     IO,
 };
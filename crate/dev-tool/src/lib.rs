@@ -42,10 +42,7 @@ pub fn run(mut cmd: impl BorrowMut<Command>) -> IO<()> {
     if status.success() {
         Ok(())
     } else {
-        Err(io::Error::new(
-            io::ErrorKind::Other,
-            format!("Subprocess failed: {status:?}"),
-        ))
+        Err(io::Error::other(format!("Subprocess failed: {status:?}")))
     }
 }
 
@@ -142,8 +139,8 @@ pub fn doc_serve() -> IO<()> {
         let addr = SocketAddr::from(([127, 0, 0, 1], port));
         eprintln!("listening on {}", addr);
         let stop = async { stop_rx.await.unwrap() };
-        axum::Server::bind(&addr)
-            .serve(app.into_make_service())
+        let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
+        axum::serve(listener, app)
             .with_graceful_shutdown(stop)
             .await
             .unwrap();